@@ -0,0 +1,325 @@
+use super::{
+    file_operations::create_file_if_missing,
+    helper_funcs::{
+        get_option, guild_id_if_admin, respond_with_content, truncate, MAX_EMBED_DESCRIPTION_LENGTH,
+    },
+};
+use dashmap::DashMap;
+use serenity::{
+    client::Context,
+    model::{
+        id::{ChannelId, GuildId, UserId},
+        prelude::{
+            interaction::application_command::{
+                ApplicationCommandInteraction, CommandDataOptionValue,
+            },
+            User,
+        },
+        Timestamp,
+    },
+    prelude::{Mentionable, RwLock, TypeMap, TypeMapKey},
+    utils::Colour,
+};
+use std::{error::Error, fs, sync::Arc};
+use tokio::sync::RwLockWriteGuard;
+
+/// Guild, Channel
+pub struct AuditLogChannels;
+impl TypeMapKey for AuditLogChannels {
+    type Value = Arc<DashMap<u64, u64>>;
+}
+pub const AUDIT_LOG_CHANNELS_PATH: &str = "data/audit log channels.json";
+
+/// Something that happened in a guild that's sent to its audit log channel
+pub enum AuditLogEvent {
+    TagSaved {
+        listener: String,
+        response: String,
+        /// If it replaced an existing tag
+        overwritten: bool,
+    },
+    TagEdited {
+        listener: String,
+        old_response: String,
+        new_response: String,
+    },
+    TagRemoved {
+        listener: String,
+    },
+    TagTransferred {
+        listener: String,
+        new_owner: UserId,
+    },
+    TagAliasAdded {
+        listener: String,
+        alias: String,
+    },
+    TagAliasRemoved {
+        listener: String,
+        alias: String,
+    },
+    TagsImported {
+        count: usize,
+    },
+    /// Someone tried to save a tag that isn't allowed
+    TagRejected {
+        listener: String,
+        reason: String,
+    },
+    MarkovBlacklistChanged {
+        blacklisted: bool,
+    },
+    /// The description is the response the admin got
+    SettingChanged(String),
+}
+
+impl AuditLogEvent {
+    fn title(&self) -> &'static str {
+        match self {
+            Self::TagSaved {
+                overwritten: false, ..
+            } => "Tag created",
+            Self::TagSaved {
+                overwritten: true, ..
+            } => "Tag overwritten",
+            Self::TagEdited { .. } => "Tag edited",
+            Self::TagRemoved { .. } => "Tag removed",
+            Self::TagTransferred { .. } => "Tag transferred",
+            Self::TagAliasAdded { .. } => "Tag alias added",
+            Self::TagAliasRemoved { .. } => "Tag alias removed",
+            Self::TagsImported { .. } => "Tags imported",
+            Self::TagRejected { .. } => "Tag rejected",
+            Self::MarkovBlacklistChanged { .. } => "Message saving changed",
+            Self::SettingChanged(_) => "Setting changed",
+        }
+    }
+
+    fn description(&self) -> String {
+        let description = match self {
+            Self::TagSaved {
+                listener, response, ..
+            } => format!("**{listener}**\n{response}"),
+            // Both responses are shortened so a long old one doesn't push the new one out
+            Self::TagEdited {
+                listener,
+                old_response,
+                new_response,
+            } => format!(
+                "**{listener}**\nBefore: {}\nAfter: {}",
+                truncate(old_response, MAX_EMBED_DESCRIPTION_LENGTH / 2 - 100),
+                truncate(new_response, MAX_EMBED_DESCRIPTION_LENGTH / 2 - 100)
+            ),
+            Self::TagRemoved { listener } => format!("**{listener}**"),
+            Self::TagTransferred {
+                listener,
+                new_owner,
+            } => format!("**{listener}** now belongs to <@{new_owner}>"),
+            Self::TagAliasAdded { listener, alias } => {
+                format!("**{alias}** now trips off **{listener}**")
+            }
+            Self::TagAliasRemoved { listener, alias } => {
+                format!("**{alias}** no longer trips off **{listener}**")
+            }
+            Self::TagsImported { count } => format!("Imported {count} tags"),
+            Self::TagRejected { listener, reason } => format!("**{listener}**: {reason}"),
+            Self::MarkovBlacklistChanged { blacklisted: true } => {
+                "Stopped saving their messages".to_owned()
+            }
+            Self::MarkovBlacklistChanged { blacklisted: false } => {
+                "Continued saving their messages".to_owned()
+            }
+            Self::SettingChanged(description) => description.clone(),
+        };
+
+        truncate(&description, MAX_EMBED_DESCRIPTION_LENGTH)
+    }
+}
+
+/// Sends the event to the audit log channel of the guild, if it has one
+pub async fn log_event(
+    ctx: &Context,
+    guild_id: Option<GuildId>,
+    user: &User,
+    event: AuditLogEvent,
+) {
+    let Some(guild_id) = guild_id else {
+        return;
+    };
+
+    let Some(channel_id) = get_audit_log_channels_lock(&ctx.data)
+        .await
+        .get(&guild_id.0)
+        .map(|channel_id| ChannelId(*channel_id))
+    else {
+        return;
+    };
+
+    send_audit_log_entry(ctx, guild_id, channel_id, user, &event).await;
+}
+
+async fn send_audit_log_entry(
+    ctx: &Context,
+    guild_id: GuildId,
+    channel_id: ChannelId,
+    user: &User,
+    event: &AuditLogEvent,
+) {
+    let colour = Colour::from_rgb(149, 8, 2);
+
+    if let Err(why) = channel_id
+        .send_message(&ctx.http, |m| {
+            m.embed(|e| {
+                e.title(event.title())
+                    .description(event.description())
+                    .colour(colour)
+                    .field("By", user.mention(), true)
+                    .timestamp(Timestamp::now())
+            })
+        })
+        .await
+    {
+        eprintln!("Couldn't send to the audit log channel of {guild_id}: {why}");
+    }
+}
+
+/// Logs a change to the settings of the guild the command was used in
+pub async fn log_setting_change(
+    ctx: &Context,
+    command: &ApplicationCommandInteraction,
+    description: impl ToString,
+) {
+    log_event(
+        ctx,
+        command.guild_id,
+        &command.user,
+        AuditLogEvent::SettingChanged(description.to_string()),
+    )
+    .await;
+}
+
+/// Sets the channel tag, message saving and settings changes are logged to, the current one if no channel is given
+pub async fn set_audit_log_channel(ctx: &Context, command: &ApplicationCommandInteraction) {
+    let Some(guild_id) = guild_id_if_admin(ctx, command).await else {
+        return;
+    };
+
+    let channel_id = match get_option(command, "channel") {
+        Some(CommandDataOptionValue::Channel(channel)) => channel.id,
+        _ => command.channel_id,
+    };
+
+    let audit_log_channels = get_audit_log_channels_lock(&ctx.data).await;
+    audit_log_channels.insert(guild_id.0, channel_id.0);
+
+    let saved = save_audit_log_channels(&audit_log_channels).is_ok();
+    let response = if saved {
+        format!("Changes will be logged in <#{channel_id}>")
+    } else {
+        "Something went wrong while setting the audit log channel".to_owned()
+    };
+
+    respond_with_content(ctx, command, &response).await;
+
+    if saved {
+        log_setting_change(ctx, command, response).await;
+    }
+}
+
+pub async fn unset_audit_log_channel(ctx: &Context, command: &ApplicationCommandInteraction) {
+    let Some(guild_id) = guild_id_if_admin(ctx, command).await else {
+        return;
+    };
+
+    let audit_log_channels = get_audit_log_channels_lock(&ctx.data).await;
+
+    let Some((_, channel_id)) = audit_log_channels.remove(&guild_id.0) else {
+        respond_with_content(ctx, command, "There is no audit log channel set").await;
+        return;
+    };
+
+    // Put back so logging keeps working if the file still has the channel
+    if save_audit_log_channels(&audit_log_channels).is_err() {
+        audit_log_channels.insert(guild_id.0, channel_id);
+        respond_with_content(
+            ctx,
+            command,
+            "Something went wrong while removing the audit log channel",
+        )
+        .await;
+        return;
+    }
+
+    let response = "Changes will no longer be logged";
+    respond_with_content(ctx, command, response).await;
+
+    // Sent to the removed channel so its last entry says logging was turned off
+    send_audit_log_entry(
+        ctx,
+        guild_id,
+        ChannelId(channel_id),
+        &command.user,
+        &AuditLogEvent::SettingChanged(response.to_owned()),
+    )
+    .await;
+}
+
+fn save_audit_log_channels(audit_log_channels: &DashMap<u64, u64>) -> Result<(), std::io::Error> {
+    fs::write(
+        AUDIT_LOG_CHANNELS_PATH,
+        serde_json::to_string(audit_log_channels).expect("Serialization failed"),
+    )
+}
+
+pub async fn get_audit_log_channels_lock(data: &Arc<RwLock<TypeMap>>) -> Arc<DashMap<u64, u64>> {
+    let audit_log_channels_lock = data
+        .read()
+        .await
+        .get::<AuditLogChannels>()
+        .expect("expected AuditLogChannels in TypeMap")
+        .clone();
+    audit_log_channels_lock
+}
+
+pub fn init_audit_log_data(data: &mut RwLockWriteGuard<TypeMap>) -> Result<(), Box<dyn Error>> {
+    let audit_log_channels: DashMap<u64, u64> = serde_json::from_str(&fs::read_to_string(
+        create_file_if_missing(AUDIT_LOG_CHANNELS_PATH, "{}")?,
+    )?)?;
+    data.insert::<AuditLogChannels>(Arc::new(audit_log_channels));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AuditLogEvent;
+    use crate::client::helper_funcs::MAX_EMBED_DESCRIPTION_LENGTH;
+
+    #[test]
+    fn long_tag_descriptions_are_truncated() {
+        let event = AuditLogEvent::TagSaved {
+            listener: "tag".to_owned(),
+            response: "a".repeat(5000),
+            overwritten: false,
+        };
+        assert_eq!(
+            event.description().chars().count(),
+            MAX_EMBED_DESCRIPTION_LENGTH
+        );
+
+        let event = AuditLogEvent::TagEdited {
+            listener: "tag".to_owned(),
+            old_response: "a".repeat(5000),
+            new_response: "b".repeat(5000),
+        };
+        let description = event.description();
+        assert!(description.chars().count() <= MAX_EMBED_DESCRIPTION_LENGTH);
+        assert!(description.contains("After: bbb"));
+    }
+
+    #[test]
+    fn short_descriptions_are_kept() {
+        let event = AuditLogEvent::TagRemoved {
+            listener: "tag".to_owned(),
+        };
+        assert_eq!(event.description(), "**tag**");
+    }
+}
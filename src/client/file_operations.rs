@@ -17,4 +17,7 @@ pub fn create_data_folders() {
     if !Path::new("data/markov data").exists() {
         fs::create_dir("data/markov data").expect("Couldn't create directory data/markov data");
     };
+    if !Path::new("data/tag assets").exists() {
+        fs::create_dir("data/tag assets").expect("Couldn't create directory data/tag assets");
+    }
 }
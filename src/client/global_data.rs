@@ -1,4 +1,7 @@
-use super::{markov::init_markov_data, tags::init_tags_data};
+use super::{
+    audit_log::init_audit_log_data, markov::init_markov_data, tags::init_tags_data,
+    voice::global_data::init_voice_data,
+};
 use crate::markov;
 use serenity::Client;
 use std::error::Error;
@@ -9,11 +12,29 @@ pub const HELP_MESSAGE: &str = "All of my commands are slash commands.
 /blacklisted-data: lists out the users the bot will not learn from
 /stop-saving-my-messages: blacklist yourself if you don't want the bot to store your messages and learn from them
 /continue-saving-my-messages: unblacklist yourself if you want the bot to save and learn from your messages
-/create-tag: create a tag that the bot will listen for and then respond to when it is said
-/remove-tag: remove a tag
-/tags: list out the current tags
-/blacklist-me-from-tags: blacklist yourself from tags so the bot won't ping you if you trip off a tag
+/create-tag: create a tag that the bot will listen for and then respond to when it is said, the response can have an embed and an attachment
+/remove-tag: remove a tag, only the creator of the tag, tag managers and admins can remove it
+/tag-transfer: give the ownership of a tag to someone else
+/tags: list out the current tags, you can search for tags and sort them by name, usage or when they were created
+/tags-export: for admins only, download every tag as a JSON or YAML file
+/tags-import: for admins only, add the tags from a file made with /tags-export
+/tag-info: show who created a tag, when it was created and edited and how many times it was tripped off
+/tag-edit: change the response of a tag
+/tag-alias add/remove: make other words trip off an existing tag, removing the tag removes its aliases
+/tag-ping-preference: choose if the bot pings you, mentions you without a ping or doesn't respond at all when you trip off a tag
 /set-tag-response-channel: for admins only, set the channel the bot will talk in, if you don't want users using the bot anywhere else you'll have to do it with roles
+/unset-tag-response-channel: for admins only, stop replying to tags in the tag response channel
+/tags-in-channel: for admins only, enable or disable tags in a channel
+/tag-channel-mode: for admins only, choose if tags work in every channel except disabled ones or only in enabled ones
+/tag-moderation: for admins only, limit tag lengths, ban words, block invite links and choose which words are too common to be tags
+/tag-reply-mode: for admins only, choose if tag responses reply to the message that tripped them off and if those replies ping
+/set-tag-cooldown: for admins only, set how long a tag has to wait before it can be tripped off again, either for every tag or just one
+/set-tag-user-cooldown: for admins only, set how long a user has to wait before they can trip off another tag
+/set-tag-manager-role: for admins only, set the role that can edit and remove every tag
+/set-tag-creator-role: for admins only, only let a role create tags
+/set-dj-role: for admins only, only let a role and admins control the songs, like skipping, pausing and changing the volume, everyone can if no role is set
+/set-audit-log-channel: for admins only, log tag changes, message saving changes and setting changes in a channel
+/unset-audit-log-channel: for admins only, stop logging changes
 /version: Check the version of the bot";
 
 /// Initialize the global data for the client so it can be used from multiple threads.
@@ -32,6 +53,10 @@ pub async fn init_global_data_for_client(client: &Client) -> Result<(), Box<dyn
 
     init_markov_data(&mut data, markov)?;
 
+    init_audit_log_data(&mut data)?;
+
+    init_voice_data(&mut data)?;
+
     init_tags_data(data)?;
 
     Ok(())
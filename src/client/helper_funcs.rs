@@ -1,11 +1,25 @@
 use serenity::{
     client::Context,
     model::prelude::{
-        interaction::application_command::{ApplicationCommandInteraction, CommandDataOptionValue},
-        Ready,
+        command::CommandOptionType,
+        interaction::{
+            application_command::{ApplicationCommandInteraction, CommandDataOptionValue},
+            message_component::MessageComponentInteraction,
+            MessageFlags,
+        },
+        GuildId, Ready,
     },
 };
 
+/// Discord doesn't allow more than 25 autocomplete choices
+pub const MAX_AUTOCOMPLETE_CHOICES: usize = 25;
+/// The most characters Discord allows in an embed title
+pub const MAX_EMBED_TITLE_LENGTH: usize = 256;
+/// The most characters Discord allows in an embed description
+pub const MAX_EMBED_DESCRIPTION_LENGTH: usize = 4096;
+/// The most characters Discord allows in the value of an embed field
+pub const MAX_EMBED_FIELD_LENGTH: usize = 1024;
+
 pub async fn user_id_command(ctx: Context, command: &ApplicationCommandInteraction) {
     let options = command
         .data
@@ -76,3 +90,131 @@ pub async fn leave_unknown_guilds(ready: &Ready, ctx: &Context) {
         }
     }
 }
+
+/// Responds to the command with a plain text message
+pub async fn respond_with_content(
+    ctx: &Context,
+    command: &ApplicationCommandInteraction,
+    content: impl ToString,
+) {
+    command
+        .create_interaction_response(&ctx.http, |r| {
+            r.interaction_response_data(|d| d.content(content))
+        })
+        .await
+        .expect("Error creating interaction response");
+}
+
+/// Responds to the button or menu with a message only the user who used it can see
+pub async fn respond_ephemeral(
+    ctx: &Context,
+    component: &MessageComponentInteraction,
+    content: impl ToString,
+) {
+    component
+        .create_interaction_response(&ctx.http, |r| {
+            r.interaction_response_data(|d| d.content(content).flags(MessageFlags::EPHEMERAL))
+        })
+        .await
+        .expect("Error creating interaction response");
+}
+
+/// Finds the value of the option with the given name, looking inside the subcommand if one was used
+pub fn get_option<'a>(
+    command: &'a ApplicationCommandInteraction,
+    name: &str,
+) -> Option<&'a CommandDataOptionValue> {
+    let options = match command.data.options.first() {
+        Some(subcommand) if subcommand.kind == CommandOptionType::SubCommand => &subcommand.options,
+        _ => &command.data.options,
+    };
+
+    options
+        .iter()
+        .find(|opt| opt.name == name)?
+        .resolved
+        .as_ref()
+}
+
+/// Checks if the user who invoked the command has the Administrator permission or owns the bot
+pub async fn is_admin_or_owner(ctx: &Context, command: &ApplicationCommandInteraction) -> bool {
+    if let Some(member) = &command.member {
+        if member
+            .permissions
+            .is_some_and(serenity::model::Permissions::administrator)
+        {
+            return true;
+        }
+    }
+
+    command.user.id
+        == ctx
+            .http
+            .get_current_application_info()
+            .await
+            .expect("Couldn't fetch the owner id")
+            .owner
+            .id
+}
+
+/// Checks if the user who invoked the command has the role in the guild it was invoked in
+pub fn member_has_role(command: &ApplicationCommandInteraction, role_id: u64) -> bool {
+    command
+        .member
+        .as_ref()
+        .is_some_and(|member| member.roles.iter().any(|role| role.0 == role_id))
+}
+
+/// Returns the guild the command was invoked in if the user is allowed to change its settings.
+///
+/// Otherwise it responds to the command with the reason and returns [`None`]
+pub async fn guild_id_if_admin(
+    ctx: &Context,
+    command: &ApplicationCommandInteraction,
+) -> Option<GuildId> {
+    let Some(guild_id) = command.guild_id else {
+        respond_with_content(ctx, command, "You can only use this command in a server").await;
+        return None;
+    };
+
+    if !is_admin_or_owner(ctx, command).await {
+        respond_with_content(
+            ctx,
+            command,
+            "You need to have the Administrator permission to invoke this command",
+        )
+        .await;
+        return None;
+    }
+
+    Some(guild_id)
+}
+
+/// Cuts the text down to `max_length` characters, ending it with … if anything was cut off
+pub fn truncate(text: &str, max_length: usize) -> String {
+    if text.chars().count() <= max_length {
+        return text.to_owned();
+    }
+
+    text.chars()
+        .take(max_length - 1)
+        .chain(std::iter::once('…'))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::truncate;
+
+    #[test]
+    fn short_text_is_kept() {
+        assert_eq!(truncate("song", 4), "song");
+        assert_eq!(truncate("", 4), "");
+    }
+
+    #[test]
+    fn long_text_is_cut_off() {
+        assert_eq!(truncate("songs", 4), "son…");
+        assert_eq!(truncate("ééééé", 3).chars().count(), 3);
+    }
+}
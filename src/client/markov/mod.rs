@@ -13,7 +13,10 @@ use self::{
     },
     markov_chain::filter_message_for_markov_file,
 };
-use super::file_operations::create_file_if_missing;
+use super::{
+    audit_log::{log_event, AuditLogEvent},
+    file_operations::create_file_if_missing,
+};
 use dashmap::DashSet;
 use markov_strings::Markov;
 use serenity::{
@@ -133,8 +136,9 @@ pub async fn add_user_to_blacklist(
 
     blacklisted_users.insert(user.id.0);
 
-    let response = match save_markov_blacklisted_users(&*blacklisted_users) {
-        Ok(_) => format!(
+    let saved = save_markov_blacklisted_users(&blacklisted_users);
+    let response = match saved {
+        Ok(()) => format!(
             "Added {} to data collection blacklist",
             match command.guild_id {
                 Some(guild_id) => user
@@ -154,6 +158,16 @@ pub async fn add_user_to_blacklist(
         })
         .await
         .expect("Error creating interaction response");
+
+    if saved.is_ok() {
+        log_event(
+            ctx,
+            command.guild_id,
+            user,
+            AuditLogEvent::MarkovBlacklistChanged { blacklisted: true },
+        )
+        .await;
+    }
 }
 
 pub async fn remove_user_from_blacklist(
@@ -164,8 +178,9 @@ pub async fn remove_user_from_blacklist(
     let blacklisted_users = get_markov_blacklisted_users_lock(&ctx.data).await;
 
     blacklisted_users.remove(&user.id.0);
-    let response = match save_markov_blacklisted_users(&*blacklisted_users) {
-        Ok(_) => format!(
+    let saved = save_markov_blacklisted_users(&blacklisted_users);
+    let response = match saved {
+        Ok(()) => format!(
             "removed {} from data collection blacklist",
             match command.guild_id {
                 Some(guild_id) => user
@@ -185,6 +200,16 @@ pub async fn remove_user_from_blacklist(
         })
         .await
         .expect("Error creating interaction response");
+
+    if saved.is_ok() {
+        log_event(
+            ctx,
+            command.guild_id,
+            user,
+            AuditLogEvent::MarkovBlacklistChanged { blacklisted: false },
+        )
+        .await;
+    }
 }
 
 pub async fn blacklisted_users(ctx: Context, command: &ApplicationCommandInteraction) {
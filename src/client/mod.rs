@@ -1,3 +1,4 @@
+pub mod audit_log;
 pub mod file_operations;
 pub mod global_data;
 pub mod helper_funcs;
@@ -9,11 +10,20 @@ pub mod voice;
 use file_operations::create_file_if_missing;
 use global_data::{init_global_data_for_client, HELP_MESSAGE};
 use helper_funcs::leave_unknown_guilds;
-use slash_commands::{command_responses, create_global_commands, create_test_commands};
+use slash_commands::{
+    autocomplete_responses, command_responses, create_global_commands, create_test_commands,
+};
 
 use self::{
-    tags::{blacklist_user, respond_to_tag},
-    voice::{edit_queue, helper_funcs::leave_vc_if_alone},
+    tags::{
+        check_tag_cooldowns, count_tag_trigger, edit_tag_list, flush_pending_tag_triggers,
+        flush_tag_triggers_periodically, respond_to_tag, set_tag_ping_preference_button,
+        show_tag_ping_settings, tags_enabled_in_channel,
+    },
+    voice::{
+        cancel_search, discard_queue_button, edit_queue, helper_funcs::leave_vc_if_alone,
+        pick_search_result, restore_queue_button, restore_sessions,
+    },
 };
 use super::tags::check_for_tag_listeners;
 use serenity::{
@@ -23,7 +33,7 @@ use serenity::{
         channel::Message,
         gateway::Ready,
         id::UserId,
-        prelude::interaction::{Interaction, InteractionType, MessageFlags},
+        prelude::interaction::{Interaction, InteractionType},
         voice::VoiceState,
     },
     prelude::GatewayIntents,
@@ -42,9 +52,20 @@ use tokio::join;
 
 #[derive(Display, EnumString, PartialEq)]
 pub enum ButtonIds {
+    /// Sent with tag responses before [`TagPingSettings`][Self::TagPingSettings] replaced it
     BlacklistMeFromTags,
+    TagPingSettings,
+    TagPingMe,
+    TagPingNoPing,
+    TagPingIgnore,
     QueueNext,
     QueuePrevious,
+    SearchResultPicker,
+    SearchCancel,
+    RestoreQueue,
+    DiscardQueue,
+    TagsNext,
+    TagsPrevious,
 }
 
 struct Handler {}
@@ -54,7 +75,7 @@ impl EventHandler for Handler {
     /// Is called when the bot connects to discord
     async fn ready(&self, ctx: Context, ready: Ready) {
         println!("{} is connected!", ready.user.name);
-        
+
         leave_unknown_guilds(&ready, &ctx).await;
 
         let t1 = create_global_commands(&ctx);
@@ -65,6 +86,8 @@ impl EventHandler for Handler {
         } else {
             t1.await;
         }
+
+        restore_sessions(&ctx).await;
     }
     /// Is called when a user starts an [`Interaction`]
     async fn interaction_create(&self, ctx: Context, interaction: Interaction) {
@@ -76,6 +99,12 @@ impl EventHandler for Handler {
                 );
                 command_responses(&command, ctx).await;
             }
+            InteractionType::Autocomplete => {
+                let autocomplete = interaction
+                    .autocomplete()
+                    .expect("it's already known that this is an Autocomplete and shouldn't break");
+                autocomplete_responses(&autocomplete, &ctx).await;
+            }
             InteractionType::MessageComponent => {
                 let mut button = interaction.message_component().expect(
                     "it's already known that this is a message component and shouldn't break",
@@ -85,20 +114,22 @@ impl EventHandler for Handler {
                     ButtonIds::from_str(&button.data.custom_id).expect("unexpected button ID");
 
                 match button_id {
-                    ButtonIds::BlacklistMeFromTags => {
-                        let response = blacklist_user(&ctx, &button.user).await;
-
-                        button
-                            .create_interaction_response(&ctx.http, |r| {
-                                r.interaction_response_data(|d| {
-                                    d.content(response).flags(MessageFlags::EPHEMERAL)
-                                })
-                            })
-                            .await
-                            .expect("couldn't create response");
+                    ButtonIds::BlacklistMeFromTags | ButtonIds::TagPingSettings => {
+                        show_tag_ping_settings(&ctx, &button).await;
+                    }
+                    ButtonIds::TagPingMe | ButtonIds::TagPingNoPing | ButtonIds::TagPingIgnore => {
+                        set_tag_ping_preference_button(&ctx, &button, &button_id).await;
+                    }
+                    ButtonIds::QueueNext | ButtonIds::QueuePrevious => {
+                        edit_queue(&ctx, &mut button, button_id).await;
+                    }
+                    ButtonIds::SearchResultPicker => pick_search_result(&ctx, &button).await,
+                    ButtonIds::SearchCancel => cancel_search(&ctx, &button).await,
+                    ButtonIds::RestoreQueue => restore_queue_button(&ctx, &button).await,
+                    ButtonIds::DiscardQueue => discard_queue_button(&ctx, &button).await,
+                    ButtonIds::TagsNext | ButtonIds::TagsPrevious => {
+                        edit_tag_list(&ctx, &mut button, button_id).await;
                     }
-                    ButtonIds::QueueNext => edit_queue(&ctx, &mut button, button_id).await,
-                    ButtonIds::QueuePrevious => edit_queue(&ctx, &mut button, button_id).await,
                 };
             }
             _ => {}
@@ -120,10 +151,29 @@ impl EventHandler for Handler {
             .map(ToString::to_string)
             .collect::<Vec<String>>();
 
-        if let Some(response) =
+        let tags_enabled = match msg.guild_id {
+            Some(guild_id) => tags_enabled_in_channel(&ctx, guild_id, msg.channel_id).await,
+            None => true,
+        };
+
+        let tag = if tags_enabled {
             check_for_tag_listeners(&ctx, &words_in_message, msg.author.id).await
-        {
-            respond_to_tag(&ctx, &msg, &response).await;
+        } else {
+            None
+        };
+
+        if let Some(tag) = tag {
+            if let Some(guild_id) = msg.guild_id {
+                if let Err(why) =
+                    check_tag_cooldowns(&ctx, guild_id, msg.author.id, &tag.listener).await
+                {
+                    println!("Didn't respond to {}: {why}", msg.author.name);
+                    return;
+                }
+            }
+
+            count_tag_trigger(&ctx, &tag).await;
+            respond_to_tag(&ctx, &msg, &tag).await;
             return;
         }
 
@@ -192,5 +242,19 @@ pub async fn start() {
         .await
         .expect("Couldn't initialize global data");
 
+    flush_tag_triggers_periodically(client.data.clone());
+
+    // Saves what's only kept in memory before shutting down
+    let data = client.data.clone();
+    let shard_manager = client.shard_manager.clone();
+    tokio::spawn(async move {
+        tokio::signal::ctrl_c()
+            .await
+            .expect("Couldn't listen for the shutdown signal");
+        println!("Shutting down");
+        flush_pending_tag_triggers(&data).await;
+        shard_manager.lock().await.shutdown_all().await;
+    });
+
     client.start().await.expect("Couldn't start the client");
 }
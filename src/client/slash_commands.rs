@@ -1,15 +1,27 @@
 use std::str::FromStr;
 
 use super::{
+    audit_log::{set_audit_log_channel, unset_audit_log_channel},
     helper_funcs::{ping_command, user_id_command},
     tags::{
-        blacklist_user_from_tags_command, create_tag, list, remove_tag, set_tag_response_channel, commands::TagCommandBuilder,
-    }, voice::commands::VoiceCommandBuilder,
+        autocomplete_tag_name, commands::TagCommandBuilder, create_tag, edit_tag, export_tags,
+        import_tags, list, remove_tag, set_tag_channel_mode, set_tag_cooldown,
+        set_tag_creator_role, set_tag_manager_role, set_tag_ping_preference, set_tag_reply_mode,
+        set_tag_response_channel, set_tag_user_cooldown, set_tags_enabled_in_channel, tag_alias,
+        tag_info, tag_moderation, transfer_tag, unset_tag_response_channel,
+    },
+    voice::commands::VoiceCommandBuilder,
 };
 use crate::{global_data, markov, voice, GuildId};
 use serenity::{
     client::Context,
-    model::{prelude::{interaction::application_command::ApplicationCommandInteraction, command::Command}},
+    model::prelude::{
+        command::Command,
+        interaction::{
+            application_command::ApplicationCommandInteraction,
+            autocomplete::AutocompleteInteraction,
+        },
+    },
     model::application::command::CommandOptionType,
 };
 use strum_macros::{Display, EnumString};
@@ -31,16 +43,58 @@ pub enum UserCommand {
     #[strum(serialize = "remove-tag")]
     removetag,
     tags,
-    #[strum(serialize = "blacklist-me-from-tags")]
-    blacklistmefromtags,
+    #[strum(serialize = "tag-ping-preference")]
+    tagpingpreference,
     #[strum(serialize = "set-tag-response-channel")]
     settagresponsechannel,
+    #[strum(serialize = "unset-tag-response-channel")]
+    unsettagresponsechannel,
+    #[strum(serialize = "tags-in-channel")]
+    tagsinchannel,
+    #[strum(serialize = "tag-channel-mode")]
+    tagchannelmode,
+    #[strum(serialize = "tag-reply-mode")]
+    tagreplymode,
+    #[strum(serialize = "tag-moderation")]
+    tagmoderation,
+    #[strum(serialize = "set-tag-cooldown")]
+    settagcooldown,
+    #[strum(serialize = "set-tag-user-cooldown")]
+    settagusercooldown,
+    #[strum(serialize = "tag-transfer")]
+    tagtransfer,
+    #[strum(serialize = "tags-export")]
+    tagsexport,
+    #[strum(serialize = "tags-import")]
+    tagsimport,
+    #[strum(serialize = "tag-info")]
+    taginfo,
+    #[strum(serialize = "tag-edit")]
+    tagedit,
+    #[strum(serialize = "tag-alias")]
+    tagalias,
+    #[strum(serialize = "set-tag-manager-role")]
+    settagmanagerrole,
+    #[strum(serialize = "set-tag-creator-role")]
+    settagcreatorrole,
+    #[strum(serialize = "set-audit-log-channel")]
+    setauditlogchannel,
+    #[strum(serialize = "unset-audit-log-channel")]
+    unsetauditlogchannel,
     help,
     version,
 
     // =====VOICE=====
     play,
+    #[strum(serialize = "play-file")]
+    play_file,
+    #[strum(serialize = "play-local")]
+    play_local,
+    search,
     skip,
+    pause,
+    resume,
+    seek,
     stop,
     playing,
     queue,
@@ -48,6 +102,17 @@ pub enum UserCommand {
     loop_song,
     #[strum(serialize = "swap-songs")]
     swap_songs,
+    shuffle,
+    #[strum(serialize = "move")]
+    move_song,
+    remove,
+    #[strum(serialize = "remove-by")]
+    remove_by,
+    clear,
+    playlist,
+    volume,
+    #[strum(serialize = "set-dj-role")]
+    set_dj_role,
 }
 
 /// Check which slash command was triggered, call the appropriate function and return a response to the user
@@ -65,11 +130,28 @@ pub async fn command_responses(command: &ApplicationCommandInteraction, ctx: Con
             UserCommand::createtag => create_tag(&ctx, command).await,
             UserCommand::removetag => remove_tag(&ctx, command).await,
             UserCommand::tags => list(&ctx, command).await,
-            UserCommand::blacklistmefromtags => {
-                blacklist_user_from_tags_command(&ctx, user, command).await;
-            }
+            UserCommand::tagpingpreference => set_tag_ping_preference(&ctx, command).await,
 
             UserCommand::settagresponsechannel => set_tag_response_channel(&ctx, command).await,
+            UserCommand::unsettagresponsechannel => {
+                unset_tag_response_channel(&ctx, command).await;
+            }
+            UserCommand::tagsinchannel => set_tags_enabled_in_channel(&ctx, command).await,
+            UserCommand::tagchannelmode => set_tag_channel_mode(&ctx, command).await,
+            UserCommand::tagreplymode => set_tag_reply_mode(&ctx, command).await,
+            UserCommand::tagmoderation => tag_moderation(&ctx, command).await,
+            UserCommand::settagcooldown => set_tag_cooldown(&ctx, command).await,
+            UserCommand::settagusercooldown => set_tag_user_cooldown(&ctx, command).await,
+            UserCommand::tagtransfer => transfer_tag(&ctx, command).await,
+            UserCommand::tagsexport => export_tags(&ctx, command).await,
+            UserCommand::tagsimport => import_tags(&ctx, command).await,
+            UserCommand::taginfo => tag_info(&ctx, command).await,
+            UserCommand::tagedit => edit_tag(&ctx, command).await,
+            UserCommand::tagalias => tag_alias(&ctx, command).await,
+            UserCommand::settagmanagerrole => set_tag_manager_role(&ctx, command).await,
+            UserCommand::settagcreatorrole => set_tag_creator_role(&ctx, command).await,
+            UserCommand::setauditlogchannel => set_audit_log_channel(&ctx, command).await,
+            UserCommand::unsetauditlogchannel => unset_audit_log_channel(&ctx, command).await,
             UserCommand::help => command
                 .create_interaction_response(ctx.http, |r| {
                     r.interaction_response_data(|d| d.content(global_data::HELP_MESSAGE))
@@ -90,12 +172,26 @@ pub async fn command_responses(command: &ApplicationCommandInteraction, ctx: Con
 
             // ===== VOICE =====
             UserCommand::play => voice::play(&ctx, command).await,
+            UserCommand::play_file => voice::play_file(&ctx, command).await,
+            UserCommand::play_local => voice::play_local(&ctx, command).await,
+            UserCommand::search => voice::search(&ctx, command).await,
             UserCommand::skip => voice::skip(&ctx, command).await,
+            UserCommand::pause => voice::pause(&ctx, command).await,
+            UserCommand::resume => voice::resume(&ctx, command).await,
+            UserCommand::seek => voice::seek(&ctx, command).await,
             UserCommand::stop => voice::stop(&ctx, command).await,
             UserCommand::playing => voice::playing(&ctx, command).await,
             UserCommand::queue => voice::queue(&ctx, command).await,
             UserCommand::loop_song => voice::loop_song(&ctx, command).await,
             UserCommand::swap_songs => voice::swap_songs(&ctx, command).await,
+            UserCommand::shuffle => voice::shuffle(&ctx, command).await,
+            UserCommand::move_song => voice::move_song(&ctx, command).await,
+            UserCommand::remove => voice::remove_songs(&ctx, command).await,
+            UserCommand::remove_by => voice::remove_songs_by(&ctx, command).await,
+            UserCommand::clear => voice::clear_queue(&ctx, command).await,
+            UserCommand::playlist => voice::playlist(&ctx, command).await,
+            UserCommand::volume => voice::volume(&ctx, command).await,
+            UserCommand::set_dj_role => voice::set_dj_role(&ctx, command).await,
         },
         Err(why) => {
             eprintln!("Cannot respond to slash command {why}");
@@ -103,6 +199,24 @@ pub async fn command_responses(command: &ApplicationCommandInteraction, ctx: Con
     };
 }
 
+/// Check which slash command is being autocompleted and suggest choices for the focused option
+pub async fn autocomplete_responses(autocomplete: &AutocompleteInteraction, ctx: &Context) {
+    match UserCommand::from_str(&autocomplete.data.name) {
+        Ok(
+            UserCommand::removetag
+            | UserCommand::taginfo
+            | UserCommand::tagedit
+            | UserCommand::tagalias
+            | UserCommand::tagtransfer
+            | UserCommand::settagcooldown,
+        ) => autocomplete_tag_name(ctx, autocomplete).await,
+        Ok(UserCommand::play_local) => voice::autocomplete_local_music(ctx, autocomplete).await,
+        Ok(UserCommand::playlist) => voice::autocomplete_saved_playlists(ctx, autocomplete).await,
+        Ok(user_command) => eprintln!("{user_command} doesn't have any autocomplete options"),
+        Err(why) => eprintln!("Cannot autocomplete slash command {why}"),
+    }
+}
+
 /// Create the slash commands
 pub async fn create_global_commands(ctx: &Context) {
     Command::set_global_application_commands(&ctx.http, |commands| {
@@ -137,6 +251,23 @@ pub async fn create_global_commands(ctx: &Context) {
                     "Remove yourself from the blacklist if you want me to save and learn from your messages",
                 )
             })
+            .create_application_command(|command| {
+                command
+                    .name(UserCommand::setauditlogchannel)
+                    .description("Log tag, message saving and setting changes in a channel")
+                    .create_option(|option| {
+                        option
+                            .name("channel")
+                            .description("The channel to log in, this one if left empty")
+                            .kind(CommandOptionType::Channel)
+                            .required(false)
+                    })
+            })
+            .create_application_command(|command| {
+                command
+                    .name(UserCommand::unsetauditlogchannel)
+                    .description("Stop logging changes")
+            })
             .create_application_command(|command| {
                 command
                     .name(UserCommand::help)
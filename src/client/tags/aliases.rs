@@ -0,0 +1,160 @@
+use super::{
+    file_operations::save_tags_to_file,
+    find_tag,
+    global_data::get_tags_lock,
+    permissions::can_manage_tag,
+    validation::{get_guild_tag_settings, validate_tag},
+};
+use crate::client::{
+    audit_log::{log_event, AuditLogEvent},
+    helper_funcs::{get_option, respond_with_content},
+};
+use serenity::{
+    client::Context,
+    model::prelude::interaction::application_command::{
+        ApplicationCommandInteraction, CommandDataOptionValue,
+    },
+};
+
+/// Adds or removes another listener that trips off an existing tag
+pub async fn tag_alias(ctx: &Context, command: &ApplicationCommandInteraction) {
+    let subcommand = command
+        .data
+        .options
+        .first()
+        .map(|subcommand| subcommand.name.as_str());
+
+    match subcommand {
+        Some("add") => add_tag_alias(ctx, command).await,
+        Some("remove") => remove_tag_alias(ctx, command).await,
+        _ => respond_with_content(ctx, command, "Something went wrong").await,
+    }
+}
+
+async fn add_tag_alias(ctx: &Context, command: &ApplicationCommandInteraction) {
+    let (
+        Some(CommandDataOptionValue::String(listener)),
+        Some(CommandDataOptionValue::String(alias)),
+    ) = (get_option(command, "tag"), get_option(command, "alias"))
+    else {
+        respond_with_content(ctx, command, "Couldn't add the alias").await;
+        return;
+    };
+
+    let alias = alias.to_lowercase().trim().to_owned();
+    let tags = get_tags_lock(&ctx.data).await;
+
+    let Some(mut tag) = find_tag(&tags, listener) else {
+        respond_with_content(ctx, command, "Couldn't find the tag").await;
+        return;
+    };
+
+    let settings = get_guild_tag_settings(ctx, command.guild_id).await;
+    if let Err(why) = validate_tag(
+        &alias,
+        &tag.response,
+        tag.embed.as_ref(),
+        tag.attachment.is_some(),
+        &settings,
+    ) {
+        respond_with_content(ctx, command, format!("Couldn't add the alias, {why}")).await;
+        return;
+    }
+
+    if let Some(existing_tag) = find_tag(&tags, &alias) {
+        respond_with_content(
+            ctx,
+            command,
+            format!(
+                "{alias} is already used by the tag {}",
+                existing_tag.listener
+            ),
+        )
+        .await;
+        return;
+    }
+
+    if !can_manage_tag(ctx, command, &tag).await {
+        respond_with_content(
+            ctx,
+            command,
+            "Only the creator of the tag, tag managers and administrators can add aliases to it",
+        )
+        .await;
+        return;
+    }
+
+    tags.remove(&tag);
+    tag.aliases.push(alias.clone());
+    tags.insert(tag.clone());
+    save_tags_to_file(&tags);
+
+    println!(
+        "{} added the alias {alias} to tag {}",
+        command.user.name, tag.listener
+    );
+    respond_with_content(
+        ctx,
+        command,
+        format!("{alias} now trips off the tag {}", tag.listener),
+    )
+    .await;
+
+    log_event(
+        ctx,
+        command.guild_id,
+        &command.user,
+        AuditLogEvent::TagAliasAdded {
+            listener: tag.listener,
+            alias,
+        },
+    )
+    .await;
+}
+
+async fn remove_tag_alias(ctx: &Context, command: &ApplicationCommandInteraction) {
+    let Some(CommandDataOptionValue::String(alias)) = get_option(command, "alias") else {
+        respond_with_content(ctx, command, "Couldn't remove the alias").await;
+        return;
+    };
+
+    let alias = alias.to_lowercase().trim().to_owned();
+    let tags = get_tags_lock(&ctx.data).await;
+
+    let Some(mut tag) = find_tag(&tags, &alias).filter(|tag| tag.listener != alias) else {
+        respond_with_content(ctx, command, "Couldn't find the alias").await;
+        return;
+    };
+
+    if !can_manage_tag(ctx, command, &tag).await {
+        respond_with_content(
+            ctx,
+            command,
+            "Only the creator of the tag, tag managers and administrators can remove its aliases",
+        )
+        .await;
+        return;
+    }
+
+    tags.remove(&tag);
+    tag.aliases.retain(|tag_alias| tag_alias != &alias);
+    tags.insert(tag.clone());
+    save_tags_to_file(&tags);
+
+    println!(
+        "{} removed the alias {alias} from tag {}",
+        command.user.name, tag.listener
+    );
+    respond_with_content(ctx, command, format!("Removed the alias {alias}")).await;
+
+    log_event(
+        ctx,
+        command.guild_id,
+        &command.user,
+        AuditLogEvent::TagAliasRemoved {
+            listener: tag.listener,
+            alias,
+        },
+    )
+    .await;
+}
@@ -0,0 +1,191 @@
+use super::{
+    global_data::{TagEmbed, TAG_ASSETS_PATH},
+    Tag,
+};
+use crate::client::helper_funcs::get_option;
+use serenity::{
+    builder::{CreateEmbed, CreateMessage},
+    model::{
+        channel::{Attachment, AttachmentType},
+        prelude::interaction::application_command::{
+            ApplicationCommandInteraction, CommandDataOptionValue,
+        },
+        Timestamp,
+    },
+};
+use std::{fs, path::Path};
+use thiserror::Error;
+
+/// Bots can't upload files bigger than 8MB to servers without boosts
+const MAX_TAG_ATTACHMENT_SIZE: u64 = 8 * 1024 * 1024;
+
+#[derive(Debug, Error)]
+pub enum TagAttachmentError {
+    #[error("the file can't be bigger than 8MB")]
+    TooLarge,
+    #[error("couldn't download the file")]
+    Download(#[from] serenity::Error),
+    #[error("couldn't save the file")]
+    Save(#[from] std::io::Error),
+}
+
+#[derive(Debug, Error)]
+pub enum TagEmbedError {
+    #[error("the embed colour has to be a hex code like #950802")]
+    InvalidColour,
+    #[error("the embed image has to be an http or https link")]
+    InvalidImageUrl,
+}
+
+/// Downloads the attachment to [`TAG_ASSETS_PATH`] and returns the path it was saved to
+pub async fn save_tag_attachment(attachment: &Attachment) -> Result<String, TagAttachmentError> {
+    if attachment.size > MAX_TAG_ATTACHMENT_SIZE {
+        return Err(TagAttachmentError::TooLarge);
+    }
+
+    let file = attachment.download().await?;
+
+    let file_name: String = attachment
+        .filename
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_'))
+        .collect();
+    // The random part keeps files uploaded in the same second with the same name apart
+    let path = format!(
+        "{TAG_ASSETS_PATH}/{}-{:08x}-{file_name}",
+        Timestamp::now().unix_timestamp(),
+        rand::random::<u32>()
+    );
+
+    fs::write(&path, file)?;
+
+    Ok(path)
+}
+
+/// Deletes the file that's sent with the tag, if it has one
+pub fn remove_tag_attachment(tag: &Tag) {
+    if let Some(path) = &tag.attachment {
+        if let Err(why) = fs::remove_file(path) {
+            eprintln!(
+                "Couldn't remove the attachment of tag {}: {why}",
+                tag.listener
+            );
+        }
+    }
+}
+
+/// Creates the tag embed from the embed options of the command, if any of them were used
+pub fn get_tag_embed_option(
+    command: &ApplicationCommandInteraction,
+) -> Result<Option<TagEmbed>, TagEmbedError> {
+    let get_string_option = |name| match get_option(command, name) {
+        Some(CommandDataOptionValue::String(value)) => Some(value.trim().to_owned()),
+        _ => None,
+    };
+
+    let colour = match get_string_option("embed-colour") {
+        Some(colour) => Some(parse_embed_colour(&colour)?),
+        None => None,
+    };
+
+    let image_url = get_string_option("embed-image");
+    if let Some(url) = &image_url {
+        if !is_http_url(url) {
+            return Err(TagEmbedError::InvalidImageUrl);
+        }
+    }
+
+    let embed = TagEmbed {
+        title: get_string_option("embed-title"),
+        description: get_string_option("embed-description"),
+        colour,
+        image_url,
+    };
+
+    if embed.title.is_none() && embed.description.is_none() && embed.image_url.is_none() {
+        return Ok(None);
+    }
+
+    Ok(Some(embed))
+}
+
+/// Parses a hex colour like `#950802`, rejecting anything past `#FFFFFF`
+fn parse_embed_colour(colour: &str) -> Result<u32, TagEmbedError> {
+    match u32::from_str_radix(colour.trim_start_matches('#'), 16) {
+        Ok(colour) if colour <= 0xFF_FF_FF => Ok(colour),
+        _ => Err(TagEmbedError::InvalidColour),
+    }
+}
+
+fn is_http_url(url: &str) -> bool {
+    let url = url.to_lowercase();
+    ["http://", "https://"]
+        .iter()
+        .any(|scheme| url.len() > scheme.len() && url.starts_with(scheme))
+}
+
+fn create_embed(tag_embed: &TagEmbed) -> CreateEmbed {
+    let mut embed = CreateEmbed::default();
+
+    if let Some(title) = &tag_embed.title {
+        embed.title(title);
+    }
+    if let Some(description) = &tag_embed.description {
+        embed.description(description);
+    }
+    if let Some(colour) = tag_embed.colour {
+        embed.colour(colour);
+    }
+    if let Some(image_url) = &tag_embed.image_url {
+        embed.image(image_url);
+    }
+
+    embed
+}
+
+/// Adds the content, the embed and the attachment of the tag to the message
+pub fn add_tag_response<'a, 'b>(
+    m: &'b mut CreateMessage<'a>,
+    tag: &'a Tag,
+    content: String,
+) -> &'b mut CreateMessage<'a> {
+    if !content.trim().is_empty() {
+        m.content(content);
+    }
+    if let Some(embed) = &tag.embed {
+        m.set_embed(create_embed(embed));
+    }
+    if let Some(path) = &tag.attachment {
+        m.add_file(AttachmentType::Path(Path::new(path)));
+    }
+    m
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_http_url, parse_embed_colour};
+
+    #[test]
+    fn colours_are_parsed() {
+        assert_eq!(parse_embed_colour("#950802").unwrap(), 0x95_08_02);
+        assert_eq!(parse_embed_colour("ffffff").unwrap(), 0xFF_FF_FF);
+    }
+
+    #[test]
+    fn invalid_colours_are_rejected() {
+        assert!(parse_embed_colour("#1000000").is_err());
+        assert!(parse_embed_colour("#ffffffff").is_err());
+        assert!(parse_embed_colour("red").is_err());
+        assert!(parse_embed_colour("").is_err());
+    }
+
+    #[test]
+    fn only_http_image_urls_are_accepted() {
+        assert!(is_http_url("https://example.com/image.png"));
+        assert!(is_http_url("HTTP://example.com/image.png"));
+        assert!(!is_http_url("file:///etc/passwd"));
+        assert!(!is_http_url("attachment://image.png"));
+        assert!(!is_http_url("https://"));
+        assert!(!is_http_url("image.png"));
+    }
+}
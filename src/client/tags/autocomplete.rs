@@ -0,0 +1,113 @@
+use super::global_data::get_tags_lock;
+use crate::client::helper_funcs::MAX_AUTOCOMPLETE_CHOICES;
+use serenity::{
+    client::Context, model::prelude::interaction::autocomplete::AutocompleteInteraction,
+};
+
+/// Suggests the tags whose listeners or aliases best match what the user has typed so far
+pub async fn autocomplete_tag_name(ctx: &Context, autocomplete: &AutocompleteInteraction) {
+    let input = autocomplete
+        .data
+        .options
+        .iter()
+        .flat_map(|option| std::iter::once(option).chain(option.options.iter()))
+        .find(|option| option.focused)
+        .and_then(|option| option.value.as_ref())
+        .and_then(|value| value.as_str())
+        .unwrap_or_default()
+        .to_lowercase();
+
+    let tags = get_tags_lock(&ctx.data).await;
+
+    let mut matches: Vec<(usize, String)> = tags
+        .iter()
+        .filter_map(|tag| {
+            tag.names()
+                .filter_map(|name| fuzzy_match_score(name, input.trim()))
+                .min()
+                .map(|score| (score, tag.listener.clone()))
+        })
+        .collect();
+    matches.sort_unstable();
+
+    autocomplete
+        .create_autocomplete_response(&ctx.http, |r| {
+            for (_, listener) in matches.into_iter().take(MAX_AUTOCOMPLETE_CHOICES) {
+                r.add_string_choice(&listener, &listener);
+            }
+            r
+        })
+        .await
+        .expect("Error creating autocomplete response");
+}
+
+/// Scores how well the listener matches the input, lower is better.
+///
+/// Exact matches come first, then listeners starting with the input, then ones containing it
+/// and lastly ones containing all of the input's characters in order.
+/// Returns [`None`] if the listener doesn't match at all.
+fn fuzzy_match_score(listener: &str, input: &str) -> Option<usize> {
+    if input.is_empty() || listener == input {
+        return Some(0);
+    }
+
+    if listener.starts_with(input) {
+        return Some(1);
+    }
+
+    if let Some(position) = listener.find(input) {
+        return Some(2 + position);
+    }
+
+    let mut listener_chars = listener.char_indices();
+    let mut last_match = 0;
+    let mut gaps = 0;
+
+    for input_char in input.chars() {
+        let (index, _) = listener_chars.find(|(_, c)| *c == input_char)?;
+        gaps += index - last_match;
+        last_match = index;
+    }
+
+    Some(listener.len() + gaps)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::fuzzy_match_score;
+
+    #[test]
+    fn exact_matches_and_empty_input_come_first() {
+        assert_eq!(fuzzy_match_score("cat", "cat"), Some(0));
+        assert_eq!(fuzzy_match_score("cat", ""), Some(0));
+    }
+
+    #[test]
+    fn prefixes_come_before_substrings() {
+        let prefix = fuzzy_match_score("catalog", "cat").unwrap();
+        let substring = fuzzy_match_score("bobcat", "cat").unwrap();
+
+        assert_eq!(prefix, 1);
+        assert!(prefix < substring);
+        assert!(fuzzy_match_score("a cat", "cat").unwrap() < substring);
+    }
+
+    #[test]
+    fn subsequences_come_last() {
+        let substring = fuzzy_match_score("bobcat", "cat").unwrap();
+        let subsequence = fuzzy_match_score("crabhat", "cat").unwrap();
+
+        assert!(substring < subsequence);
+        assert!(
+            fuzzy_match_score("cxat", "cat").unwrap()
+                < fuzzy_match_score("cxxxxxat", "cat").unwrap()
+        );
+    }
+
+    #[test]
+    fn non_matches_are_rejected() {
+        assert_eq!(fuzzy_match_score("dog", "cat"), None);
+        assert_eq!(fuzzy_match_score("tac", "cat"), None);
+        assert_eq!(fuzzy_match_score("ca", "cat"), None);
+    }
+}
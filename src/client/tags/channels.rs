@@ -0,0 +1,121 @@
+use super::{file_operations::save_tag_settings, global_data::get_tag_settings_lock};
+use crate::client::{
+    audit_log::log_setting_change,
+    helper_funcs::{get_option, guild_id_if_admin, respond_with_content},
+};
+use serenity::{
+    client::Context,
+    model::{
+        id::{ChannelId, GuildId},
+        prelude::interaction::application_command::{
+            ApplicationCommandInteraction, CommandDataOptionValue,
+        },
+    },
+};
+
+/// Checks if tags should be responded to in the channel
+pub async fn tags_enabled_in_channel(
+    ctx: &Context,
+    guild_id: GuildId,
+    channel_id: ChannelId,
+) -> bool {
+    let tag_settings = get_tag_settings_lock(&ctx.data).await;
+
+    let Some(settings) = tag_settings.get(&guild_id.0) else {
+        return true;
+    };
+    // Threads follow the settings of the channel they're in
+    let channel_id = thread_parent(ctx, guild_id, channel_id).unwrap_or(channel_id);
+
+    if settings.allowlist_enabled {
+        return settings.allowed_channels.contains(&channel_id.0);
+    }
+
+    !settings.disabled_channels.contains(&channel_id.0)
+}
+
+/// The channel the thread was created in, `None` if the channel isn't a thread the bot knows of
+fn thread_parent(ctx: &Context, guild_id: GuildId, channel_id: ChannelId) -> Option<ChannelId> {
+    ctx.cache
+        .guild_field(guild_id, |guild| {
+            guild
+                .threads
+                .iter()
+                .find(|thread| thread.id == channel_id)
+                .and_then(|thread| thread.parent_id)
+        })
+        .flatten()
+}
+
+/// Enables or disables tags in a channel, the current one if no channel is given
+pub async fn set_tags_enabled_in_channel(ctx: &Context, command: &ApplicationCommandInteraction) {
+    let Some(guild_id) = guild_id_if_admin(ctx, command).await else {
+        return;
+    };
+
+    let Some(CommandDataOptionValue::Boolean(enabled)) = get_option(command, "enabled") else {
+        respond_with_content(ctx, command, "Something went wrong").await;
+        return;
+    };
+    let channel_id = match get_option(command, "channel") {
+        Some(CommandDataOptionValue::Channel(channel)) => channel.id,
+        _ => command.channel_id,
+    };
+
+    let tag_settings = get_tag_settings_lock(&ctx.data).await;
+    {
+        let mut settings = tag_settings.entry(guild_id.0).or_default();
+        if *enabled {
+            settings.disabled_channels.remove(&channel_id.0);
+            settings.allowed_channels.insert(channel_id.0);
+        } else {
+            settings.disabled_channels.insert(channel_id.0);
+            settings.allowed_channels.remove(&channel_id.0);
+        }
+    }
+
+    let saved = save_tag_settings(&tag_settings).is_ok();
+    let response = match (saved, enabled) {
+        (true, true) => format!("Enabled tags in <#{channel_id}>"),
+        (true, false) => format!("Disabled tags in <#{channel_id}>"),
+        (false, _) => "Something went wrong while saving the tag channels".to_owned(),
+    };
+
+    respond_with_content(ctx, command, &response).await;
+
+    if saved {
+        log_setting_change(ctx, command, response).await;
+    }
+}
+
+/// Switches between responding to tags everywhere except disabled channels
+/// and only responding to them in enabled channels
+pub async fn set_tag_channel_mode(ctx: &Context, command: &ApplicationCommandInteraction) {
+    let Some(guild_id) = guild_id_if_admin(ctx, command).await else {
+        return;
+    };
+
+    let allowlist_enabled = matches!(
+        get_option(command, "mode"),
+        Some(CommandDataOptionValue::String(mode)) if mode == "allowlist"
+    );
+
+    let tag_settings = get_tag_settings_lock(&ctx.data).await;
+    tag_settings
+        .entry(guild_id.0)
+        .or_default()
+        .allowlist_enabled = allowlist_enabled;
+
+    let saved = save_tag_settings(&tag_settings).is_ok();
+    let response = match (saved, allowlist_enabled) {
+        (true, true) => "Tags now only work in channels they were enabled in",
+        (true, false) => "Tags now work in every channel they weren't disabled in",
+        (false, _) => "Something went wrong while saving the tag channel mode",
+    };
+
+    respond_with_content(ctx, command, &response).await;
+
+    if saved {
+        log_setting_change(ctx, command, response).await;
+    }
+}
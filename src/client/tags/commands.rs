@@ -2,6 +2,7 @@ use serenity::{
     builder::CreateApplicationCommands, model::prelude::command::CommandOptionType,
 };
 
+use super::{list::{TagSort, MAX_TAG_SEARCH_LENGTH}, ImportConflictPolicy, TagFileFormat, TagPingPreference};
 use crate::client::slash_commands::UserCommand;
 
 /// Create the tag slash commands
@@ -11,6 +12,7 @@ pub trait TagCommandBuilder {
 }
 
 impl TagCommandBuilder for CreateApplicationCommands {
+    #[allow(clippy::too_many_lines)]
     fn create_tag_commands(&mut self) -> &mut Self {
         self.create_application_command(|command| {
             command.name(UserCommand::createtag).description(
@@ -22,24 +24,227 @@ impl TagCommandBuilder for CreateApplicationCommands {
             .create_option(|option|{
                 option.name("response").description("What the response should be when the tag is said")
                 .kind(CommandOptionType::String)
-                .required(true)
+                .required(false)
+            })
+            .create_option(|option| {
+                option.name("attachment").description("An image or file to send with the response").kind(CommandOptionType::Attachment).required(false)
+            })
+            .create_option(|option| {
+                option.name("embed-title").description("The title of an embed to send with the response").kind(CommandOptionType::String).required(false)
+            })
+            .create_option(|option| {
+                option.name("embed-description").description("The text of an embed to send with the response").kind(CommandOptionType::String).required(false)
+            })
+            .create_option(|option| {
+                option.name("embed-colour").description("The colour of the embed as a hex code like #950802").kind(CommandOptionType::String).required(false)
+            })
+            .create_option(|option| {
+                option.name("embed-image").description("A link to an image to show in the embed").kind(CommandOptionType::String).required(false)
             })
         })
         .create_application_command(|command| {
             command.name(UserCommand::removetag).description("Remove a tag").create_option(|option|{
-                option.name("tag").description("The tag to remove").kind(CommandOptionType::String).required(true)
+                option.name("tag").description("The tag to remove").kind(CommandOptionType::String).set_autocomplete(true).required(true)
             })
         })
         .create_application_command(|command|{
             command.name(UserCommand::tags).description("List all of the tags")
+            .create_option(|option| {
+                option.name("search").description("Only list tags containing this").kind(CommandOptionType::String).max_length(MAX_TAG_SEARCH_LENGTH).required(false)
+            })
+            .create_option(|option| {
+                option.name("sort").description("The order to list the tags in").kind(CommandOptionType::String).required(false)
+                .add_string_choice("Name", TagSort::Name)
+                .add_string_choice("Most used", TagSort::Usage)
+                .add_string_choice("Newest", TagSort::Newest)
+            })
         })
-        .create_application_command(|command|{
-            command.name(UserCommand::blacklistmefromtags).description("The bot won't respond to your messages if you trip off a tag")
+        .create_application_command(|command| {
+            command.name(UserCommand::tagsexport).description("Download every tag as a file that can be imported with /tags-import")
+            .create_option(|option| {
+                option.name("format").description("The file format, JSON if left empty").kind(CommandOptionType::String).required(false)
+                .add_string_choice("JSON", TagFileFormat::Json)
+                .add_string_choice("YAML", TagFileFormat::Yaml)
+            })
+        })
+        .create_application_command(|command| {
+            command.name(UserCommand::tagsimport).description("Add the tags from a file made with /tags-export")
+            .create_option(|option| {
+                option.name("file").description("A JSON or YAML file").kind(CommandOptionType::Attachment).required(true)
+            })
+            .create_option(|option| {
+                option.name("conflicts").description("What to do with tags that already exist, skip them if left empty").kind(CommandOptionType::String).required(false)
+                .add_string_choice("Skip", ImportConflictPolicy::Skip)
+                .add_string_choice("Overwrite", ImportConflictPolicy::Overwrite)
+                .add_string_choice("Rename", ImportConflictPolicy::Rename)
+            })
+        })
+        .create_application_command(|command| {
+            command.name(UserCommand::taginfo).description("Show who created a tag, when and how many times it was tripped off")
+            .create_option(|option| {
+                option.name("tag").description("The tag to show").kind(CommandOptionType::String).set_autocomplete(true).required(true)
+            })
+        })
+        .create_application_command(|command| {
+            command.name(UserCommand::tagalias).description("Add or remove other words that trip off a tag")
+            .create_option(|option| {
+                option.name("add").description("Make another word trip off a tag").kind(CommandOptionType::SubCommand)
+                .create_sub_option(|option| {
+                    option.name("tag").description("The tag to add the alias to").kind(CommandOptionType::String).set_autocomplete(true).required(true)
+                })
+                .create_sub_option(|option| {
+                    option.name("alias").description("The word that should also trip off the tag").kind(CommandOptionType::String).required(true)
+                })
+            })
+            .create_option(|option| {
+                option.name("remove").description("Remove an alias from its tag").kind(CommandOptionType::SubCommand)
+                .create_sub_option(|option| {
+                    option.name("alias").description("The alias to remove").kind(CommandOptionType::String).required(true)
+                })
+            })
+        })
+        .create_application_command(|command| {
+            command.name(UserCommand::tagedit).description("Change the response of a tag")
+            .create_option(|option| {
+                option.name("tag").description("The tag to edit").kind(CommandOptionType::String).set_autocomplete(true).required(true)
+            })
+            .create_option(|option| {
+                option.name("response").description("The new response").kind(CommandOptionType::String).required(true)
+            })
+        })
+        .create_application_command(|command| {
+            command.name(UserCommand::tagpingpreference).description("Choose how I respond when you trip off a tag")
+            .create_option(|option| {
+                option.name("preference").description("Whether I ping you, only mention you or don't respond at all").kind(CommandOptionType::String).required(true)
+                .add_string_choice("Ping me", TagPingPreference::Ping)
+                .add_string_choice("Reply without pinging me", TagPingPreference::NoPing)
+                .add_string_choice("Don't respond to me", TagPingPreference::Ignore)
+            })
         })
         .create_application_command(|command| {
             command.name(UserCommand::settagresponsechannel).description(
                 "Set this channel as the channel where i will reply to tags",
             )
         })
+        .create_application_command(|command| {
+            command.name(UserCommand::unsettagresponsechannel).description(
+                "Stop replying to tags in the tag response channel",
+            )
+        })
+        .create_application_command(|command| {
+            command.name(UserCommand::tagsinchannel).description("Enable or disable tags in a channel")
+            .create_option(|option| {
+                option.name("enabled").description("Whether tags should work in the channel").kind(CommandOptionType::Boolean).required(true)
+            })
+            .create_option(|option| {
+                option.name("channel").description("The channel, this one if left empty").kind(CommandOptionType::Channel).required(false)
+            })
+        })
+        .create_application_command(|command| {
+            command.name(UserCommand::tagchannelmode).description("Choose if tags work in every channel or only in the ones they were enabled in")
+            .create_option(|option| {
+                option.name("mode").description("Where tags work").kind(CommandOptionType::String).required(true)
+                .add_string_choice("Every channel except disabled ones", "all")
+                .add_string_choice("Only enabled channels", "allowlist")
+            })
+        })
+        .create_application_command(|command| {
+            command.name(UserCommand::tagreplymode).description("Choose if tag responses reply to the message that tripped them off")
+            .create_option(|option| {
+                option.name("enabled").description("Reply to the message, or link to it if the response is sent in another channel").kind(CommandOptionType::Boolean).required(true)
+            })
+            .create_option(|option| {
+                option.name("ping").description("If the reply pings the user, users who turned off pings are never pinged").kind(CommandOptionType::Boolean).required(false)
+            })
+        })
+        .create_application_command(|command| {
+            command.name(UserCommand::tagmoderation).description("Choose what tags can contain")
+            .create_option(|option| {
+                option.name("show").description("Show the current tag moderation settings").kind(CommandOptionType::SubCommand)
+            })
+            .create_option(|option| {
+                option.name("max-length").description("Limit how long tags and responses can be").kind(CommandOptionType::SubCommand)
+                .create_sub_option(|option| {
+                    option.name("listener").description("The most characters a tag can have, 0 for no limit").kind(CommandOptionType::Integer).min_int_value(0).required(false)
+                })
+                .create_sub_option(|option| {
+                    option.name("response").description("The most characters a response can have, 0 for no limit").kind(CommandOptionType::Integer).min_int_value(0).required(false)
+                })
+            })
+            .create_option(|option| {
+                option.name("ban-word").description("Stop a word from being used anywhere in a tag").kind(CommandOptionType::SubCommand)
+                .create_sub_option(|option| {
+                    option.name("word").description("The word to ban").kind(CommandOptionType::String).required(true)
+                })
+            })
+            .create_option(|option| {
+                option.name("unban-word").description("Let a banned word be used in tags again").kind(CommandOptionType::SubCommand)
+                .create_sub_option(|option| {
+                    option.name("word").description("The word to unban").kind(CommandOptionType::String).required(true)
+                })
+            })
+            .create_option(|option| {
+                option.name("block-invites").description("Choose if tags can contain discord invite links").kind(CommandOptionType::SubCommand)
+                .create_sub_option(|option| {
+                    option.name("enabled").description("Block invite links").kind(CommandOptionType::Boolean).required(true)
+                })
+            })
+            .create_option(|option| {
+                option.name("add-stopword").description("Stop a word that's too common from being a tag on its own").kind(CommandOptionType::SubCommand)
+                .create_sub_option(|option| {
+                    option.name("word").description("The common word").kind(CommandOptionType::String).required(true)
+                })
+            })
+            .create_option(|option| {
+                option.name("remove-stopword").description("Let a stopword be a tag again").kind(CommandOptionType::SubCommand)
+                .create_sub_option(|option| {
+                    option.name("word").description("The stopword to remove").kind(CommandOptionType::String).required(true)
+                })
+            })
+        })
+        .create_application_command(|command| {
+            command.name(UserCommand::settagcooldown).description(
+                "Set how long a tag has to wait before it can be tripped off again",
+            )
+            .create_option(|option| {
+                option.name("seconds").description("The cooldown in seconds").kind(CommandOptionType::Integer).min_int_value(0).required(true)
+            })
+            .create_option(|option| {
+                option.name("tag").description("Only change the cooldown of this tag").kind(CommandOptionType::String).set_autocomplete(true).required(false)
+            })
+        })
+        .create_application_command(|command| {
+            command.name(UserCommand::settagusercooldown).description(
+                "Set how long a user has to wait before they can trip off another tag",
+            )
+            .create_option(|option| {
+                option.name("seconds").description("The cooldown in seconds").kind(CommandOptionType::Integer).min_int_value(0).required(true)
+            })
+        })
+        .create_application_command(|command| {
+            command.name(UserCommand::tagtransfer).description("Give the ownership of a tag to someone else")
+            .create_option(|option| {
+                option.name("tag").description("The tag to transfer").kind(CommandOptionType::String).set_autocomplete(true).required(true)
+            })
+            .create_option(|option| {
+                option.name("user").description("The new owner of the tag").kind(CommandOptionType::User).required(true)
+            })
+        })
+        .create_application_command(|command| {
+            command.name(UserCommand::settagmanagerrole).description(
+                "Set the role that can edit and remove every tag, leave empty to remove it",
+            )
+            .create_option(|option| {
+                option.name("role").description("The tag manager role").kind(CommandOptionType::Role).required(false)
+            })
+        })
+        .create_application_command(|command| {
+            command.name(UserCommand::settagcreatorrole).description(
+                "Only let this role create tags, leave empty to let everyone create them",
+            )
+            .create_option(|option| {
+                option.name("role").description("The role that can create tags").kind(CommandOptionType::Role).required(false)
+            })
+        })
     }
 }
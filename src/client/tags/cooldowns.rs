@@ -0,0 +1,163 @@
+use super::{
+    file_operations::save_tag_settings,
+    global_data::{get_tag_cooldowns_lock, get_tag_settings_lock},
+};
+use crate::client::{
+    audit_log::log_setting_change,
+    helper_funcs::{get_option, guild_id_if_admin, respond_with_content},
+};
+use serenity::{
+    client::Context,
+    model::{
+        id::{GuildId, UserId},
+        prelude::interaction::application_command::{
+            ApplicationCommandInteraction, CommandDataOptionValue,
+        },
+    },
+};
+use std::time::{Duration, Instant};
+use thiserror::Error;
+
+/// The reason a tripped tag wasn't responded to
+#[derive(Debug, Error)]
+pub enum TagSuppressed {
+    #[error("the tag {listener} is on cooldown for another {remaining}s")]
+    TagOnCooldown { listener: String, remaining: u64 },
+    #[error("the user is on cooldown for another {remaining}s")]
+    UserOnCooldown { remaining: u64 },
+}
+
+/// Checks if the tag or the user who tripped it is still on cooldown.
+///
+/// If neither is, both cooldowns are restarted.
+pub async fn check_tag_cooldowns(
+    ctx: &Context,
+    guild_id: GuildId,
+    user_id: UserId,
+    listener: &str,
+) -> Result<(), TagSuppressed> {
+    let settings = match get_tag_settings_lock(&ctx.data).await.get(&guild_id.0) {
+        Some(settings) => settings.clone(),
+        None => return Ok(()),
+    };
+    let cooldowns = get_tag_cooldowns_lock(&ctx.data).await;
+
+    let tag_cooldown = Duration::from_secs(
+        *settings
+            .tag_cooldowns
+            .get(listener)
+            .unwrap_or(&settings.cooldown),
+    );
+    let user_cooldown = Duration::from_secs(settings.user_cooldown);
+
+    let tag_key = (guild_id.0, listener.to_owned());
+    let user_key = (guild_id.0, user_id.0);
+
+    if let Some(remaining) =
+        remaining_cooldown(cooldowns.users.get(&user_key).as_deref(), user_cooldown)
+    {
+        return Err(TagSuppressed::UserOnCooldown { remaining });
+    }
+
+    if let Some(remaining) =
+        remaining_cooldown(cooldowns.tags.get(&tag_key).as_deref(), tag_cooldown)
+    {
+        return Err(TagSuppressed::TagOnCooldown {
+            listener: listener.to_owned(),
+            remaining,
+        });
+    }
+
+    let now = Instant::now();
+    cooldowns.tags.insert(tag_key, now);
+    cooldowns.users.insert(user_key, now);
+
+    Ok(())
+}
+
+/// Returns how many seconds are left on a cooldown that started at `last_triggered`
+fn remaining_cooldown(last_triggered: Option<&Instant>, cooldown: Duration) -> Option<u64> {
+    let remaining = cooldown.checked_sub(last_triggered?.elapsed())?;
+
+    if remaining.is_zero() {
+        None
+    } else {
+        Some(remaining.as_secs() + 1)
+    }
+}
+
+/// Sets the cooldown for every tag in the guild, or for a single tag if one is given
+pub async fn set_tag_cooldown(ctx: &Context, command: &ApplicationCommandInteraction) {
+    let Some(guild_id) = guild_id_if_admin(ctx, command).await else {
+        return;
+    };
+
+    let Some(seconds) = get_seconds_option(command) else {
+        respond_with_content(ctx, command, "Please provide a valid number of seconds").await;
+        return;
+    };
+
+    let listener = match get_option(command, "tag") {
+        Some(CommandDataOptionValue::String(listener)) => Some(listener.to_lowercase()),
+        _ => None,
+    };
+
+    let tag_settings = get_tag_settings_lock(&ctx.data).await;
+    {
+        let mut settings = tag_settings.entry(guild_id.0).or_default();
+        match &listener {
+            Some(listener) => {
+                settings.tag_cooldowns.insert(listener.clone(), seconds);
+            }
+            None => settings.cooldown = seconds,
+        }
+    }
+
+    let saved = save_tag_settings(&tag_settings).is_ok();
+    let response = match (saved, listener) {
+        (true, Some(listener)) => format!("Set the cooldown of {listener} to {seconds}s"),
+        (true, None) => format!("Set the tag cooldown to {seconds}s"),
+        (false, _) => "Something went wrong while setting the tag cooldown".to_owned(),
+    };
+
+    respond_with_content(ctx, command, &response).await;
+
+    if saved {
+        log_setting_change(ctx, command, response).await;
+    }
+}
+
+/// Sets how long a user has to wait before they can trip off another tag
+pub async fn set_tag_user_cooldown(ctx: &Context, command: &ApplicationCommandInteraction) {
+    let Some(guild_id) = guild_id_if_admin(ctx, command).await else {
+        return;
+    };
+
+    let Some(seconds) = get_seconds_option(command) else {
+        respond_with_content(ctx, command, "Please provide a valid number of seconds").await;
+        return;
+    };
+
+    let tag_settings = get_tag_settings_lock(&ctx.data).await;
+    tag_settings.entry(guild_id.0).or_default().user_cooldown = seconds;
+
+    let saved = save_tag_settings(&tag_settings).is_ok();
+    let response = if saved {
+        format!("Users now have to wait {seconds}s between tags")
+    } else {
+        "Something went wrong while setting the user cooldown".to_owned()
+    };
+
+    respond_with_content(ctx, command, &response).await;
+
+    if saved {
+        log_setting_change(ctx, command, response).await;
+    }
+}
+
+fn get_seconds_option(command: &ApplicationCommandInteraction) -> Option<u64> {
+    match get_option(command, "seconds")? {
+        CommandDataOptionValue::Integer(seconds) => (*seconds).try_into().ok(),
+        _ => None,
+    }
+}
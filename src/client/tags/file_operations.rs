@@ -1,16 +1,19 @@
 use super::{
-    global_data::{BLACKLISTED_USERS_PATH, BOT_CHANNEL_PATH, TAG_PATH},
-    Tag,
+    global_data::{
+        TagPingPreference, BOT_CHANNEL_PATH, TAG_PATH, TAG_PING_PREFERENCES_PATH, TAG_SETTINGS_PATH,
+    },
+    Tag, TagSettings,
 };
 use dashmap::{DashMap, DashSet};
 use std::fs;
 
-pub fn save_user_tag_blacklist_to_file(blacklist: &DashSet<u64>) {
+pub fn save_tag_ping_preferences(
+    tag_ping_preferences: &DashMap<u64, TagPingPreference>,
+) -> Result<(), std::io::Error> {
     fs::write(
-        BLACKLISTED_USERS_PATH,
-        serde_json::to_string(&blacklist).expect("Serialization failed"),
+        TAG_PING_PREFERENCES_PATH,
+        serde_json::to_string(tag_ping_preferences).expect("Serialization failed"),
     )
-    .expect("Something went wrong while writing to file.");
 }
 
 pub fn save_tags_to_file(tags: &DashSet<Tag>) {
@@ -27,3 +30,10 @@ pub fn save_tag_response_channel(bot_channels: &DashMap<u64, u64>) -> Result<(),
         serde_json::to_string(bot_channels).expect("Serialization failed"),
     )
 }
+
+pub fn save_tag_settings(tag_settings: &DashMap<u64, TagSettings>) -> Result<(), std::io::Error> {
+    fs::write(
+        TAG_SETTINGS_PATH,
+        serde_json::to_string(tag_settings).expect("Serialization failed"),
+    )
+}
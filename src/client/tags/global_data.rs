@@ -1,25 +1,112 @@
 use dashmap::{DashMap, DashSet};
 use serde::{Deserialize, Serialize};
 use serenity::prelude::{RwLock, TypeMap, TypeMapKey};
-use std::sync::Arc;
+use std::{
+    collections::{HashMap, HashSet},
+    hash::{Hash, Hasher},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use strum_macros::{Display, EnumString};
 
-#[derive(Hash, Eq, PartialEq, Clone, Serialize, Deserialize)]
+/// Tags are identified by their listener, two tags with the same listener are equal
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Tag {
     pub listener: String,
     pub response: String,
     pub creator_name: String,
     pub creator_id: u64,
+    /// Unix timestamp, 0 if the tag was created before creation dates were saved
+    #[serde(default)]
+    pub created_at: i64,
+    /// Unix timestamp of the last time the response was edited
+    #[serde(default)]
+    pub edited_at: Option<i64>,
+    /// How many times the tag was tripped off, not counting [`PendingTagTriggers`]
+    #[serde(default)]
+    pub trigger_count: u64,
+    #[serde(default)]
+    pub embed: Option<TagEmbed>,
+    /// Path of a file in [`TAG_ASSETS_PATH`] that's sent with the response
+    #[serde(default)]
+    pub attachment: Option<String>,
+    /// Other listeners that trip off this tag
+    #[serde(default)]
+    pub aliases: Vec<String>,
+}
+
+impl Tag {
+    /// The listener followed by the aliases
+    pub fn names(&self) -> impl Iterator<Item = &String> {
+        std::iter::once(&self.listener).chain(self.aliases.iter())
+    }
+}
+
+/// An embed that's sent with the response of a tag
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct TagEmbed {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub colour: Option<u32>,
+    pub image_url: Option<String>,
+}
+
+impl PartialEq for Tag {
+    fn eq(&self, other: &Self) -> bool {
+        self.listener == other.listener
+    }
+}
+
+impl Eq for Tag {}
+
+impl Hash for Tag {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.listener.hash(state);
+    }
 }
 pub struct TagsContainer;
 impl TypeMapKey for TagsContainer {
     type Value = Arc<DashSet<Tag>>;
 }
 pub const TAG_PATH: &str = "data/tags.json";
+pub const TAG_ASSETS_PATH: &str = "data/tag assets";
 
-pub struct TagBlacklistedUsers;
-impl TypeMapKey for TagBlacklistedUsers {
-    type Value = Arc<DashSet<u64>>;
+/// Listener, number of triggers that haven't been added to the [`Tag`] yet.
+///
+/// They get saved to [`TAG_PATH`] in batches so the file isn't written on every trigger
+pub struct PendingTagTriggers;
+impl TypeMapKey for PendingTagTriggers {
+    type Value = Arc<DashMap<String, u64>>;
 }
+/// How many triggers are collected before they're saved
+pub const TAG_TRIGGER_BATCH_SIZE: u64 = 20;
+/// How often the triggers are saved when there aren't enough for a batch
+pub const TAG_TRIGGER_FLUSH_INTERVAL: Duration = Duration::from_mins(5);
+
+/// How a user wants to be responded to when they trip off a tag
+#[derive(Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Display, EnumString)]
+pub enum TagPingPreference {
+    /// Mention the user when the response isn't sent in the channel they tripped off the tag in
+    #[default]
+    #[strum(serialize = "ping")]
+    Ping,
+    /// Mention the user without pinging them
+    #[strum(serialize = "no-ping")]
+    NoPing,
+    /// Don't respond to the user's messages at all
+    #[strum(serialize = "ignore")]
+    Ignore,
+}
+
+/// User, [`TagPingPreference`]. Users who haven't changed their preference aren't saved
+pub struct TagPingPreferences;
+impl TypeMapKey for TagPingPreferences {
+    type Value = Arc<DashMap<u64, TagPingPreference>>;
+}
+pub const TAG_PING_PREFERENCES_PATH: &str = "data/tag ping preferences.json";
+/// The users that blacklisted themselves from tags before ping preferences existed.
+///
+/// It's only read to move them to [`TAG_PING_PREFERENCES_PATH`]
 pub const BLACKLISTED_USERS_PATH: &str = "data/user listener blacklist.json";
 
 ///Guild, Channel
@@ -29,6 +116,63 @@ impl TypeMapKey for TagResponseChannelIds {
 }
 pub const BOT_CHANNEL_PATH: &str = "data/bot channel.json";
 
+/// Tag configuration for a single guild
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TagSettings {
+    /// How many seconds have to pass before a tag can be tripped again
+    pub cooldown: u64,
+    /// Per tag overrides of [`cooldown`][Self::cooldown], keyed by the listener
+    pub tag_cooldowns: HashMap<String, u64>,
+    /// How many seconds have to pass before the same user can trip any tag again
+    pub user_cooldown: u64,
+    /// Role that can edit and remove every tag
+    pub manager_role: Option<u64>,
+    /// If set only this role can create tags
+    pub creator_role: Option<u64>,
+    /// Channels where tags aren't responded to
+    pub disabled_channels: HashSet<u64>,
+    /// If enabled tags are only responded to in [`allowed_channels`][Self::allowed_channels]
+    pub allowlist_enabled: bool,
+    pub allowed_channels: HashSet<u64>,
+    /// If enabled responses are replies to the message that tripped off the tag,
+    /// or link to it when they're sent to another channel
+    pub reply_to_trigger: bool,
+    /// If replies ping the user who tripped off the tag
+    pub ping_on_reply: bool,
+    /// Longest listener allowed, in characters
+    pub max_listener_length: Option<usize>,
+    /// Longest response allowed, in characters
+    pub max_response_length: Option<usize>,
+    /// Words that can't be used anywhere in a tag
+    pub banned_words: HashSet<String>,
+    /// If tags can't contain discord invite links
+    pub block_invites: bool,
+    /// Words that are too common to be listeners on their own.
+    /// If it's [`None`] the [`DEFAULT_TAG_STOPWORDS`][super::validation::DEFAULT_TAG_STOPWORDS] are used
+    pub stopwords: Option<HashSet<String>>,
+}
+
+/// Guild, [`TagSettings`]
+pub struct TagSettingsContainer;
+impl TypeMapKey for TagSettingsContainer {
+    type Value = Arc<DashMap<u64, TagSettings>>;
+}
+pub const TAG_SETTINGS_PATH: &str = "data/tag settings.json";
+
+/// When tags and users last tripped off a tag. This isn't saved to a file.
+#[derive(Default)]
+pub struct TagCooldowns {
+    /// (Guild, Listener), Instant
+    pub tags: DashMap<(u64, String), Instant>,
+    /// (Guild, User), Instant
+    pub users: DashMap<(u64, u64), Instant>,
+}
+impl TypeMapKey for TagCooldowns {
+    type Value = Arc<TagCooldowns>;
+}
+
 pub async fn get_tags_lock(data: &Arc<RwLock<TypeMap>>) -> Arc<DashSet<Tag>> {
     let tag_lock = data
         .read()
@@ -39,14 +183,16 @@ pub async fn get_tags_lock(data: &Arc<RwLock<TypeMap>>) -> Arc<DashSet<Tag>> {
     tag_lock
 }
 
-pub async fn get_tags_blacklisted_users_lock(data: &Arc<RwLock<TypeMap>>) -> Arc<DashSet<u64>> {
-    let tag_blacklisted_users_lock = data
+pub async fn get_tag_ping_preferences_lock(
+    data: &Arc<RwLock<TypeMap>>,
+) -> Arc<DashMap<u64, TagPingPreference>> {
+    let tag_ping_preferences_lock = data
         .read()
         .await
-        .get::<TagBlacklistedUsers>()
-        .expect("expected TagBlacklistedUsers in TypeMap")
+        .get::<TagPingPreferences>()
+        .expect("expected TagPingPreferences in TypeMap")
         .clone();
-    tag_blacklisted_users_lock
+    tag_ping_preferences_lock
 }
 
 pub async fn get_tag_response_channel_id_lock(
@@ -60,3 +206,35 @@ pub async fn get_tag_response_channel_id_lock(
         .clone();
     bot_channel_ids_lock
 }
+
+pub async fn get_tag_settings_lock(data: &Arc<RwLock<TypeMap>>) -> Arc<DashMap<u64, TagSettings>> {
+    let tag_settings_lock = data
+        .read()
+        .await
+        .get::<TagSettingsContainer>()
+        .expect("expected TagSettingsContainer in TypeMap")
+        .clone();
+    tag_settings_lock
+}
+
+pub async fn get_tag_cooldowns_lock(data: &Arc<RwLock<TypeMap>>) -> Arc<TagCooldowns> {
+    let tag_cooldowns_lock = data
+        .read()
+        .await
+        .get::<TagCooldowns>()
+        .expect("expected TagCooldowns in TypeMap")
+        .clone();
+    tag_cooldowns_lock
+}
+
+pub async fn get_pending_tag_triggers_lock(
+    data: &Arc<RwLock<TypeMap>>,
+) -> Arc<DashMap<String, u64>> {
+    let pending_tag_triggers_lock = data
+        .read()
+        .await
+        .get::<PendingTagTriggers>()
+        .expect("expected PendingTagTriggers in TypeMap")
+        .clone();
+    pending_tag_triggers_lock
+}
@@ -0,0 +1,323 @@
+use super::{
+    assets::remove_tag_attachment,
+    file_operations::save_tags_to_file,
+    find_tag,
+    global_data::{get_tags_lock, TagEmbed},
+    validation::{get_guild_tag_settings, validate_tag},
+    Tag, TagSettings,
+};
+use crate::client::{
+    audit_log::{log_event, AuditLogEvent},
+    helper_funcs::{get_option, guild_id_if_admin, respond_with_content},
+};
+use anyhow::Result;
+use dashmap::DashSet;
+use serde::{Deserialize, Serialize};
+use serenity::{
+    client::Context,
+    model::{
+        channel::{Attachment, AttachmentType},
+        prelude::interaction::application_command::{
+            ApplicationCommandInteraction, CommandDataOptionValue,
+        },
+        Timestamp,
+    },
+};
+use std::{borrow::Cow, path::Path, str::FromStr};
+use strum_macros::{Display, EnumString};
+
+/// The parts of a [`Tag`] that can be moved to another server.
+///
+/// Attachments aren't exported because they're stored on the machine the bot runs on
+#[derive(Serialize, Deserialize)]
+struct ExportedTag {
+    listener: String,
+    #[serde(default)]
+    response: String,
+    #[serde(default)]
+    embed: Option<TagEmbed>,
+    #[serde(default)]
+    aliases: Vec<String>,
+}
+
+#[derive(Display, EnumString, Clone, Copy)]
+pub enum TagFileFormat {
+    #[strum(serialize = "json")]
+    Json,
+    #[strum(serialize = "yaml")]
+    Yaml,
+}
+
+/// What to do when an imported tag has the same listener as an existing one
+#[derive(Display, EnumString, Clone, Copy)]
+pub enum ImportConflictPolicy {
+    #[strum(serialize = "skip")]
+    Skip,
+    #[strum(serialize = "overwrite")]
+    Overwrite,
+    #[strum(serialize = "rename")]
+    Rename,
+}
+
+#[derive(Default)]
+struct ImportSummary {
+    imported: usize,
+    overwritten: usize,
+    renamed: usize,
+    skipped: usize,
+    rejected: Vec<String>,
+}
+
+impl std::fmt::Display for ImportSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Imported {} new tags, overwrote {}, renamed {} and skipped {} existing tags",
+            self.imported, self.overwritten, self.renamed, self.skipped
+        )?;
+
+        if !self.rejected.is_empty() {
+            write!(f, "\nRejected {} tags:", self.rejected.len())?;
+            for rejection in self.rejected.iter().take(10) {
+                write!(f, "\n{rejection}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Sends every tag as a JSON or YAML file
+pub async fn export_tags(ctx: &Context, command: &ApplicationCommandInteraction) {
+    if guild_id_if_admin(ctx, command).await.is_none() {
+        return;
+    }
+
+    let format = match get_option(command, "format") {
+        Some(CommandDataOptionValue::String(format)) => {
+            TagFileFormat::from_str(format).unwrap_or(TagFileFormat::Json)
+        }
+        _ => TagFileFormat::Json,
+    };
+
+    let tags = get_tags_lock(&ctx.data).await;
+    let mut exported_tags: Vec<ExportedTag> = tags
+        .iter()
+        .map(|tag| ExportedTag {
+            listener: tag.listener.clone(),
+            response: tag.response.clone(),
+            embed: tag.embed.clone(),
+            aliases: tag.aliases.clone(),
+        })
+        .collect();
+    exported_tags.sort_by(|a, b| a.listener.cmp(&b.listener));
+
+    let file = match format {
+        TagFileFormat::Json => serde_json::to_string_pretty(&exported_tags).map_err(Into::into),
+        TagFileFormat::Yaml => serde_yaml::to_string(&exported_tags).map_err(anyhow::Error::from),
+    };
+
+    let Ok(file) = file else {
+        respond_with_content(
+            ctx,
+            command,
+            "Something went wrong while exporting the tags",
+        )
+        .await;
+        return;
+    };
+
+    command
+        .create_interaction_response(&ctx.http, |r| {
+            r.interaction_response_data(|d| {
+                d.content(format!("Exported {} tags", exported_tags.len()))
+                    .add_file(AttachmentType::Bytes {
+                        data: Cow::Owned(file.into_bytes()),
+                        filename: format!("tags.{format}"),
+                    })
+            })
+        })
+        .await
+        .expect("Error creating interaction response");
+}
+
+/// Adds the tags from a JSON or YAML file made by [`export_tags`]
+pub async fn import_tags(ctx: &Context, command: &ApplicationCommandInteraction) {
+    if guild_id_if_admin(ctx, command).await.is_none() {
+        return;
+    }
+
+    let Some(CommandDataOptionValue::Attachment(attachment)) = get_option(command, "file") else {
+        respond_with_content(ctx, command, "Please upload a file to import").await;
+        return;
+    };
+    let policy = match get_option(command, "conflicts") {
+        Some(CommandDataOptionValue::String(policy)) => {
+            ImportConflictPolicy::from_str(policy).unwrap_or(ImportConflictPolicy::Skip)
+        }
+        _ => ImportConflictPolicy::Skip,
+    };
+    let extension = Path::new(&attachment.filename)
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .map(str::to_lowercase);
+    let format = match extension.as_deref() {
+        Some("yaml" | "yml") => TagFileFormat::Yaml,
+        _ => TagFileFormat::Json,
+    };
+
+    command
+        .defer(&ctx.http)
+        .await
+        .expect("Error creating interaction response");
+
+    let imported_tags = match read_tag_file(attachment, format).await {
+        Ok(imported_tags) => imported_tags,
+        Err(why) => {
+            command
+                .edit_original_interaction_response(&ctx.http, |r| {
+                    r.content(format!("Couldn't read the file: {why}"))
+                })
+                .await
+                .expect("Error creating interaction response");
+            return;
+        }
+    };
+
+    let tags = get_tags_lock(&ctx.data).await;
+    let settings = get_guild_tag_settings(ctx, command.guild_id).await;
+    let summary = add_imported_tags(&tags, imported_tags, policy, &settings, command);
+    save_tags_to_file(&tags);
+
+    let count = summary.imported + summary.overwritten + summary.renamed;
+    println!("{} imported {count} tags", command.user.name);
+    command
+        .edit_original_interaction_response(&ctx.http, |r| r.content(summary.to_string()))
+        .await
+        .expect("Error creating interaction response");
+
+    log_event(
+        ctx,
+        command.guild_id,
+        &command.user,
+        AuditLogEvent::TagsImported { count },
+    )
+    .await;
+}
+
+async fn read_tag_file(attachment: &Attachment, format: TagFileFormat) -> Result<Vec<ExportedTag>> {
+    let file = attachment.download().await?;
+
+    Ok(match format {
+        TagFileFormat::Json => serde_json::from_slice(&file)?,
+        TagFileFormat::Yaml => serde_yaml::from_slice(&file)?,
+    })
+}
+
+fn add_imported_tags(
+    tags: &DashSet<Tag>,
+    imported_tags: Vec<ExportedTag>,
+    policy: ImportConflictPolicy,
+    settings: &TagSettings,
+    command: &ApplicationCommandInteraction,
+) -> ImportSummary {
+    let mut summary = ImportSummary::default();
+
+    for imported_tag in imported_tags {
+        let mut listener = imported_tag.listener.to_lowercase().trim().to_owned();
+
+        if let Err(why) = validate_tag(
+            &listener,
+            &imported_tag.response,
+            imported_tag.embed.as_ref(),
+            false,
+            settings,
+        ) {
+            summary.rejected.push(format!("{listener}: {why}"));
+            continue;
+        }
+
+        if let Some(existing_tag) = find_tag(tags, &listener) {
+            if existing_tag.listener != listener {
+                summary.rejected.push(format!(
+                    "{listener}: it's an alias of the tag {}",
+                    existing_tag.listener
+                ));
+                continue;
+            }
+
+            match policy {
+                ImportConflictPolicy::Skip => {
+                    summary.skipped += 1;
+                    continue;
+                }
+                ImportConflictPolicy::Overwrite => {
+                    tags.remove(&existing_tag);
+                    remove_tag_attachment(&existing_tag);
+                    summary.overwritten += 1;
+                }
+                ImportConflictPolicy::Rename => {
+                    let mut number = 2;
+                    while find_tag(tags, &format!("{listener}-{number}")).is_some() {
+                        number += 1;
+                    }
+                    let renamed_listener = format!("{listener}-{number}");
+
+                    if let Err(why) = validate_tag(
+                        &renamed_listener,
+                        &imported_tag.response,
+                        imported_tag.embed.as_ref(),
+                        false,
+                        settings,
+                    ) {
+                        summary.rejected.push(format!("{renamed_listener}: {why}"));
+                        continue;
+                    }
+
+                    listener = renamed_listener;
+                    summary.renamed += 1;
+                }
+            }
+        } else {
+            summary.imported += 1;
+        }
+
+        let mut aliases = Vec::new();
+        for alias in &imported_tag.aliases {
+            let alias = alias.to_lowercase().trim().to_owned();
+            if alias == listener || find_tag(tags, &alias).is_some() {
+                continue;
+            }
+
+            if let Err(why) = validate_tag(
+                &alias,
+                &imported_tag.response,
+                imported_tag.embed.as_ref(),
+                false,
+                settings,
+            ) {
+                summary
+                    .rejected
+                    .push(format!("{alias} (alias of {listener}): {why}"));
+                continue;
+            }
+
+            aliases.push(alias);
+        }
+
+        tags.insert(Tag {
+            listener,
+            response: imported_tag.response.trim().to_owned(),
+            creator_name: command.user.name.clone(),
+            creator_id: command.user.id.0,
+            created_at: Timestamp::now().unix_timestamp(),
+            edited_at: None,
+            trigger_count: 0,
+            embed: imported_tag.embed,
+            attachment: None,
+            aliases,
+        });
+    }
+
+    summary
+}
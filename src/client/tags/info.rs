@@ -0,0 +1,73 @@
+use super::{
+    find_tag,
+    global_data::{get_pending_tag_triggers_lock, get_tags_lock},
+};
+use crate::client::helper_funcs::{
+    get_option, respond_with_content, truncate, MAX_EMBED_DESCRIPTION_LENGTH,
+    MAX_EMBED_FIELD_LENGTH, MAX_EMBED_TITLE_LENGTH,
+};
+use serenity::{
+    client::Context,
+    model::prelude::interaction::application_command::{
+        ApplicationCommandInteraction, CommandDataOptionValue,
+    },
+    utils::Colour,
+};
+
+/// Shows the response, creator, dates and trigger count of a tag
+pub async fn tag_info(ctx: &Context, command: &ApplicationCommandInteraction) {
+    let Some(CommandDataOptionValue::String(listener)) = get_option(command, "tag") else {
+        respond_with_content(ctx, command, "Something went wrong").await;
+        return;
+    };
+
+    let tags = get_tags_lock(&ctx.data).await;
+
+    let Some(tag) = find_tag(&tags, listener) else {
+        respond_with_content(ctx, command, "Couldn't find the tag").await;
+        return;
+    };
+
+    let pending_triggers = get_pending_tag_triggers_lock(&ctx.data)
+        .await
+        .get(&tag.listener)
+        .map_or(0, |count| *count);
+
+    let created_at = if tag.created_at == 0 {
+        "Unknown".to_owned()
+    } else {
+        format!("<t:{}:f>", tag.created_at)
+    };
+    let edited_at = tag.edited_at.map_or_else(
+        || "Never".to_owned(),
+        |edited_at| format!("<t:{edited_at}:f>"),
+    );
+    let aliases = if tag.aliases.is_empty() {
+        "None".to_owned()
+    } else {
+        tag.aliases.join(", ")
+    };
+    let colour = Colour::from_rgb(149, 8, 2);
+
+    command
+        .create_interaction_response(&ctx.http, |r| {
+            r.interaction_response_data(|d| {
+                d.embed(|e| {
+                    e.title(truncate(&tag.listener, MAX_EMBED_TITLE_LENGTH))
+                        .description(truncate(&tag.response, MAX_EMBED_DESCRIPTION_LENGTH))
+                        .colour(colour)
+                        .field("Creator", format!("<@{}>", tag.creator_id), true)
+                        .field("Created", created_at, true)
+                        .field("Last edited", edited_at, true)
+                        .field(
+                            "Times triggered",
+                            tag.trigger_count + pending_triggers,
+                            true,
+                        )
+                        .field("Aliases", truncate(&aliases, MAX_EMBED_FIELD_LENGTH), false)
+                })
+            })
+        })
+        .await
+        .expect("Error creating interaction response");
+}
@@ -0,0 +1,341 @@
+use super::{
+    global_data::{get_pending_tag_triggers_lock, get_tags_lock},
+    Tag,
+};
+use crate::client::{
+    helper_funcs::{get_option, truncate, MAX_EMBED_DESCRIPTION_LENGTH},
+    ButtonIds,
+};
+use serenity::{
+    builder::{CreateComponents, CreateEmbed},
+    client::Context,
+    model::prelude::{
+        component::ButtonStyle,
+        interaction::{
+            application_command::{ApplicationCommandInteraction, CommandDataOptionValue},
+            message_component::MessageComponentInteraction,
+        },
+        ReactionType,
+    },
+    utils::Colour,
+};
+use std::str::FromStr;
+use strum_macros::{Display, EnumString};
+
+const TAGS_PER_PAGE: usize = 10;
+/// Leaves room on each line for the trigger count so a full page fits in the embed description
+const MAX_LISTED_LISTENER_LENGTH: usize = MAX_EMBED_DESCRIPTION_LENGTH / TAGS_PER_PAGE - 50;
+/// Keeps the search short enough to fit in the footer, which can't be longer than 2048 characters
+pub const MAX_TAG_SEARCH_LENGTH: u16 = 100;
+
+/// The order the tags are listed in
+#[derive(Display, EnumString, Clone, Copy)]
+pub enum TagSort {
+    #[strum(serialize = "name")]
+    Name,
+    #[strum(serialize = "usage")]
+    Usage,
+    #[strum(serialize = "newest")]
+    Newest,
+}
+
+/// Which page of the tag list is shown.
+///
+/// It's stored in the footer of the embed so the page buttons know what to show next
+struct TagListPage {
+    /// Starts at 0
+    page: usize,
+    sort: TagSort,
+    search: Option<String>,
+}
+
+impl TagListPage {
+    fn to_footer(&self, page_count: usize) -> String {
+        let mut footer = format!(
+            "Page {}/{} | Sorted by {}",
+            self.page + 1,
+            page_count,
+            self.sort
+        );
+        if let Some(search) = &self.search {
+            footer.push_str(" | Search: ");
+            footer.push_str(search);
+        }
+        footer
+    }
+
+    fn from_footer(footer: &str) -> Option<Self> {
+        let mut parts = footer.splitn(3, " | ");
+
+        let page = parts
+            .next()?
+            .strip_prefix("Page ")?
+            .split('/')
+            .next()?
+            .parse::<usize>()
+            .ok()?
+            .checked_sub(1)?;
+        let sort = TagSort::from_str(parts.next()?.strip_prefix("Sorted by ")?).ok()?;
+        let search = parts
+            .next()
+            .and_then(|search| search.strip_prefix("Search: "))
+            .map(ToOwned::to_owned);
+
+        Some(Self { page, sort, search })
+    }
+}
+
+/// Lists the tags in an embed with buttons to go through the pages
+pub async fn list(ctx: &Context, command: &ApplicationCommandInteraction) {
+    let search = match get_option(command, "search") {
+        Some(CommandDataOptionValue::String(search)) => Some(
+            search
+                .to_lowercase()
+                .chars()
+                .take(MAX_TAG_SEARCH_LENGTH.into())
+                .collect(),
+        ),
+        _ => None,
+    };
+    let sort = match get_option(command, "sort") {
+        Some(CommandDataOptionValue::String(sort)) => {
+            TagSort::from_str(sort).unwrap_or(TagSort::Name)
+        }
+        _ => TagSort::Name,
+    };
+
+    let page = TagListPage {
+        page: 0,
+        sort,
+        search,
+    };
+    let tags = get_sorted_tags(ctx, &page).await;
+
+    if tags.is_empty() {
+        let response = match &page.search {
+            Some(search) => format!("No tags match {search}"),
+            None => "There are no tags".to_owned(),
+        };
+        command
+            .create_interaction_response(&ctx.http, |r| {
+                r.interaction_response_data(|d| d.content(response))
+            })
+            .await
+            .expect("Error creating interaction response");
+        return;
+    }
+
+    let embed = create_tag_list_embed(&tags, &page);
+
+    command
+        .create_interaction_response(&ctx.http, |r| {
+            r.interaction_response_data(|d| {
+                d.set_embed(embed)
+                    .components(|c| create_page_buttons(c, tags.len()))
+            })
+        })
+        .await
+        .expect("Error creating interaction response");
+}
+
+/// Goes to the next or previous page of the tag list
+pub async fn edit_tag_list(
+    ctx: &Context,
+    button: &mut MessageComponentInteraction,
+    button_id: ButtonIds,
+) {
+    button.defer(&ctx.http).await.unwrap();
+
+    let Some(mut page) = button
+        .message
+        .embeds
+        .first()
+        .and_then(|embed| embed.footer.as_ref())
+        .and_then(|footer| TagListPage::from_footer(&footer.text))
+    else {
+        return;
+    };
+
+    let tags = get_sorted_tags(ctx, &page).await;
+
+    if tags.is_empty() {
+        button
+            .edit_original_interaction_response(&ctx.http, |r| {
+                r.content("There are no tags")
+                    .set_embeds(Vec::new())
+                    .components(|c| c)
+            })
+            .await
+            .expect("Error creating interaction response");
+        return;
+    }
+
+    let page_count = tags.len().div_ceil(TAGS_PER_PAGE);
+    page.page = if button_id == ButtonIds::TagsNext {
+        (page.page + 1).min(page_count - 1)
+    } else {
+        page.page.saturating_sub(1).min(page_count - 1)
+    };
+
+    let embed = create_tag_list_embed(&tags, &page);
+
+    button
+        .edit_original_interaction_response(&ctx.http, |r| {
+            r.set_embed(embed)
+                .components(|c| create_page_buttons(c, tags.len()))
+        })
+        .await
+        .expect("Error creating interaction response");
+}
+
+/// Returns the tags that match the search, sorted, with their trigger counts
+async fn get_sorted_tags(ctx: &Context, page: &TagListPage) -> Vec<(Tag, u64)> {
+    let tags = get_tags_lock(&ctx.data).await;
+    let pending_tag_triggers = get_pending_tag_triggers_lock(&ctx.data).await;
+
+    let mut tags: Vec<(Tag, u64)> = tags
+        .iter()
+        .filter(|tag| {
+            page.search
+                .as_ref()
+                .is_none_or(|search| tag.names().any(|name| name.contains(search.as_str())))
+        })
+        .map(|tag| {
+            let pending_triggers = pending_tag_triggers
+                .get(&tag.listener)
+                .map_or(0, |count| *count);
+            let trigger_count = tag.trigger_count + pending_triggers;
+            (tag.clone(), trigger_count)
+        })
+        .collect();
+
+    match page.sort {
+        TagSort::Name => tags.sort_by(|(a, _), (b, _)| a.listener.cmp(&b.listener)),
+        TagSort::Usage => tags.sort_by(|(a, a_count), (b, b_count)| {
+            b_count
+                .cmp(a_count)
+                .then_with(|| a.listener.cmp(&b.listener))
+        }),
+        TagSort::Newest => tags.sort_by(|(a, _), (b, _)| {
+            b.created_at
+                .cmp(&a.created_at)
+                .then_with(|| a.listener.cmp(&b.listener))
+        }),
+    }
+
+    tags
+}
+
+fn create_tag_list_embed(tags: &[(Tag, u64)], page: &TagListPage) -> CreateEmbed {
+    let page_count = tags.len().div_ceil(TAGS_PER_PAGE);
+    let colour = Colour::from_rgb(149, 8, 2);
+
+    let description = tags
+        .iter()
+        .skip(page.page * TAGS_PER_PAGE)
+        .take(TAGS_PER_PAGE)
+        .map(|(tag, trigger_count)| {
+            format!(
+                "**{}** | used {trigger_count} times",
+                truncate(&tag.listener, MAX_LISTED_LISTENER_LENGTH)
+            )
+        })
+        .collect::<Vec<String>>()
+        .join("\n");
+
+    CreateEmbed::default()
+        .title(format!("Tags ({})", tags.len()))
+        .description(description)
+        .colour(colour)
+        .footer(|f| f.text(page.to_footer(page_count)))
+        .clone()
+}
+
+fn create_page_buttons(c: &mut CreateComponents, tag_count: usize) -> &mut CreateComponents {
+    if tag_count <= TAGS_PER_PAGE {
+        return c;
+    }
+
+    c.create_action_row(|a| {
+        a.create_button(|b| {
+            b.emoji(ReactionType::Unicode("◀".to_string()))
+                .style(ButtonStyle::Primary)
+                .custom_id(ButtonIds::TagsPrevious)
+        })
+        .create_button(|b| {
+            b.emoji(ReactionType::Unicode("▶".to_string()))
+                .style(ButtonStyle::Primary)
+                .custom_id(ButtonIds::TagsNext)
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn footer_round_trips() {
+        let page = TagListPage {
+            page: 2,
+            sort: TagSort::Usage,
+            search: Some("cat | dog".to_owned()),
+        };
+
+        let parsed = TagListPage::from_footer(&page.to_footer(5)).expect("footer should parse");
+
+        assert_eq!(parsed.page, 2);
+        assert!(matches!(parsed.sort, TagSort::Usage));
+        assert_eq!(parsed.search.as_deref(), Some("cat | dog"));
+    }
+
+    #[test]
+    fn footer_without_search() {
+        let parsed =
+            TagListPage::from_footer("Page 1/3 | Sorted by newest").expect("footer should parse");
+
+        assert_eq!(parsed.page, 0);
+        assert!(matches!(parsed.sort, TagSort::Newest));
+        assert!(parsed.search.is_none());
+    }
+
+    #[test]
+    fn invalid_footers_are_rejected() {
+        assert!(TagListPage::from_footer("").is_none());
+        assert!(TagListPage::from_footer("Page 0/3 | Sorted by name").is_none());
+        assert!(TagListPage::from_footer("Page x/3 | Sorted by name").is_none());
+        assert!(TagListPage::from_footer("Page 1/3 | Sorted by size").is_none());
+    }
+
+    #[test]
+    fn long_listeners_fit_in_the_page() {
+        let tags: Vec<(Tag, u64)> = (0..TAGS_PER_PAGE)
+            .map(|i| {
+                let tag = Tag {
+                    listener: format!("{i}{}", "a".repeat(1000)),
+                    response: String::new(),
+                    creator_name: String::new(),
+                    creator_id: 0,
+                    created_at: 0,
+                    edited_at: None,
+                    trigger_count: 0,
+                    embed: None,
+                    attachment: None,
+                    aliases: Vec::new(),
+                };
+                (tag, u64::MAX)
+            })
+            .collect();
+        let page = TagListPage {
+            page: 0,
+            sort: TagSort::Name,
+            search: None,
+        };
+
+        let embed = create_tag_list_embed(&tags, &page);
+        let description = embed.0["description"].as_str().unwrap();
+
+        assert!(description.chars().count() <= MAX_EMBED_DESCRIPTION_LENGTH);
+        assert_eq!(description.lines().count(), TAGS_PER_PAGE);
+    }
+}
@@ -1,61 +1,75 @@
+mod aliases;
+mod assets;
+mod autocomplete;
+mod channels;
 pub mod commands;
+mod cooldowns;
 mod file_operations;
 mod global_data;
+mod import_export;
+mod info;
+mod list;
+mod moderation;
+mod permissions;
+mod ping_preferences;
+mod replies;
+mod validation;
 
 use self::global_data::{
-    TagBlacklistedUsers, TagResponseChannelIds, TagsContainer, BLACKLISTED_USERS_PATH,
-    BOT_CHANNEL_PATH, TAG_PATH,
+    PendingTagTriggers, TagCooldowns, TagEmbed, TagPingPreferences, TagResponseChannelIds,
+    TagSettingsContainer, TagsContainer, BOT_CHANNEL_PATH, TAG_PATH, TAG_PING_PREFERENCES_PATH,
+    TAG_SETTINGS_PATH, TAG_TRIGGER_BATCH_SIZE, TAG_TRIGGER_FLUSH_INTERVAL,
 };
-use super::{create_file_if_missing, ButtonIds};
-use crate::client::tags::file_operations::save_user_tag_blacklist_to_file;
+use super::{
+    audit_log::{log_event, log_setting_change, AuditLogEvent},
+    create_file_if_missing,
+    helper_funcs::{get_option, guild_id_if_admin, respond_with_content},
+};
+pub use aliases::tag_alias;
+use assets::{add_tag_response, get_tag_embed_option, remove_tag_attachment, save_tag_attachment};
+pub use autocomplete::autocomplete_tag_name;
+pub use channels::{set_tag_channel_mode, set_tags_enabled_in_channel, tags_enabled_in_channel};
+pub use cooldowns::{check_tag_cooldowns, set_tag_cooldown, set_tag_user_cooldown};
 use dashmap::{DashMap, DashSet};
-pub use global_data::Tag;
-use regex::Regex;
+pub use global_data::{Tag, TagPingPreference, TagSettings};
+pub use import_export::{export_tags, import_tags, ImportConflictPolicy, TagFileFormat};
+pub use info::tag_info;
+pub use list::{edit_tag_list, list};
+pub use moderation::tag_moderation;
+use permissions::{can_create_tags, can_manage_tag};
+pub use permissions::{set_tag_creator_role, set_tag_manager_role, transfer_tag};
+use ping_preferences::{
+    add_mentioning_tag_response, get_tag_ping_preference, migrate_tag_blacklist,
+};
+pub use ping_preferences::{
+    set_tag_ping_preference, set_tag_ping_preference_button, show_tag_ping_settings,
+};
+use replies::get_tag_reply_mode;
+pub use replies::set_tag_reply_mode;
 use serenity::{
-    builder::ParseValue,
     client::Context,
     model::{
-        channel::{Message, Channel},
+        channel::{Channel, Message},
         guild::Guild,
         id::{ChannelId, UserId},
-        prelude::{
-            component::ButtonStyle,
-            interaction::application_command::{
-                ApplicationCommandInteraction, CommandDataOptionValue,
-            },
-            User,
+        prelude::interaction::application_command::{
+            ApplicationCommandInteraction, CommandDataOptionValue,
         },
+        Timestamp,
     },
-    prelude::{Mentionable, TypeMap},
+    prelude::{RwLock, TypeMap},
 };
 use std::{error::Error, fs, sync::Arc};
 use tokio::sync::RwLockWriteGuard;
+use validation::{get_guild_tag_settings, validate_tag, TagRejection};
 use {
     file_operations::{save_tag_response_channel, save_tags_to_file},
     global_data::{
-        get_tag_response_channel_id_lock, get_tags_blacklisted_users_lock, get_tags_lock,
+        get_pending_tag_triggers_lock, get_tag_ping_preferences_lock,
+        get_tag_response_channel_id_lock, get_tags_lock,
     },
 };
 
-pub async fn list(ctx: &Context, command: &ApplicationCommandInteraction) {
-    let tag = get_tags_lock(&ctx.data).await;
-
-    let mut message = String::new();
-
-    for tag in tag.iter() {
-        message += &format!("{}, ", tag.listener);
-    }
-    message.pop();
-    message.pop();
-
-    command
-        .create_interaction_response(&ctx.http, |r| {
-            r.interaction_response_data(|d| d.content(message))
-        })
-        .await
-        .expect("Error creating interaction response");
-}
-
 pub async fn remove_tag(ctx: &Context, command: &ApplicationCommandInteraction) {
     let listener = command
         .data
@@ -71,7 +85,24 @@ pub async fn remove_tag(ctx: &Context, command: &ApplicationCommandInteraction)
     if let CommandDataOptionValue::String(listener) = listener {
         for tag in tags.as_ref().clone().iter() {
             if &tag.listener == listener {
+                if !can_manage_tag(ctx, command, &tag).await {
+                    command
+                        .create_interaction_response(&ctx.http, |r| {
+                            r.interaction_response_data(|d| {
+                                d.content("Only the creator of the tag, tag managers and administrators can remove it")
+                            })
+                        })
+                        .await
+                        .expect("Error creating interaction response");
+                    return;
+                }
+
                 tags.remove(&tag);
+                // A new tag with the same listener starts counting from zero
+                get_pending_tag_triggers_lock(&ctx.data)
+                    .await
+                    .remove(&tag.listener);
+                remove_tag_attachment(&tag);
                 save_tags_to_file(&tags);
                 println!("{} removed tag {}", command.user.name, tag.listener);
                 response = "Successfully removed the tag";
@@ -81,6 +112,15 @@ pub async fn remove_tag(ctx: &Context, command: &ApplicationCommandInteraction)
                     })
                     .await
                     .expect("Error creating interaction response");
+                log_event(
+                    ctx,
+                    command.guild_id,
+                    &command.user,
+                    AuditLogEvent::TagRemoved {
+                        listener: tag.listener.clone(),
+                    },
+                )
+                .await;
                 return;
             }
         }
@@ -105,223 +145,372 @@ pub async fn remove_tag(ctx: &Context, command: &ApplicationCommandInteraction)
 }
 
 pub async fn create_tag(ctx: &Context, command: &ApplicationCommandInteraction) {
-    let listener = command
-        .data
-        .options
-        .get(0)
-        .expect("Expected listener option")
-        .resolved
-        .as_ref()
-        .expect("Expected listener value");
-    let response = command
-        .data
-        .options
-        .get(1)
-        .expect("Expected response option")
-        .resolved
-        .as_ref()
-        .expect("Expected response value");
+    if !can_create_tags(ctx, command).await {
+        respond_with_content(
+            ctx,
+            command,
+            "You don't have the role needed to create tags",
+        )
+        .await;
+        return;
+    }
 
-    if let CommandDataOptionValue::String(listener) = listener {
-        if let CommandDataOptionValue::String(response) = response {
-            let user_regex = Regex::new(r"<@!?(\d+)>").expect("Invalid regular expression");
-            let role_regex = Regex::new(r"<@&(\d+)>").expect("Invalid regular expression");
-            if user_regex.is_match(response)
-                || user_regex.is_match(listener)
-                || role_regex.is_match(response)
-                || role_regex.is_match(listener)
-                || response.contains("@everyone")
-                || response.contains("@here")
-            {
+    let Some(CommandDataOptionValue::String(listener)) = get_option(command, "tag") else {
+        respond_with_content(ctx, command, "Couldn't set tag").await;
+        return;
+    };
+    let response = match get_option(command, "response") {
+        Some(CommandDataOptionValue::String(response)) => response.trim().to_owned(),
+        _ => String::new(),
+    };
+    let attachment = match get_option(command, "attachment") {
+        Some(CommandDataOptionValue::Attachment(attachment)) => Some(attachment),
+        _ => None,
+    };
+    let embed = match get_tag_embed_option(command) {
+        Ok(embed) => embed,
+        Err(why) => {
+            respond_with_content(ctx, command, format!("Couldn't set tag, {why}")).await;
+            return;
+        }
+    };
+
+    if let Err(why) = check_tag(
+        ctx,
+        command,
+        listener,
+        &response,
+        embed.as_ref(),
+        attachment.is_some(),
+    )
+    .await
+    {
+        respond_with_content(ctx, command, format!("Couldn't set tag, {why}")).await;
+        return;
+    }
+
+    let listener = listener.to_lowercase().trim().to_owned();
+    let tags = get_tags_lock(&ctx.data).await;
+
+    let existing_tag = find_tag(&tags, &listener);
+    if let Some(existing_tag) = &existing_tag {
+        if let Some(why) = overwrite_rejection(ctx, command, existing_tag, &listener).await {
+            respond_with_content(ctx, command, why).await;
+            return;
+        }
+    }
+
+    // Downloading the attachment can take longer than discord waits for a response
+    command
+        .defer(&ctx.http)
+        .await
+        .expect("Error creating interaction response");
+
+    let attachment = match attachment {
+        Some(attachment) => match save_tag_attachment(attachment).await {
+            Ok(path) => Some(path),
+            Err(why) => {
                 command
-                    .create_interaction_response(&ctx.http, |r| {
-                        r.interaction_response_data(|d| d.content("can't add a mention"))
+                    .edit_original_interaction_response(&ctx.http, |r| {
+                        r.content(format!("Couldn't set tag, {why}"))
                     })
                     .await
                     .expect("Error creating interaction response");
                 return;
             }
+        },
+        None => None,
+    };
 
-            let tags = get_tags_lock(&ctx.data).await;
+    let event = AuditLogEvent::TagSaved {
+        listener: listener.clone(),
+        response: response.clone(),
+        overwritten: existing_tag.is_some(),
+    };
+    let mut aliases = Vec::new();
+    if let Some(existing_tag) = existing_tag {
+        tags.remove(&existing_tag);
+        remove_tag_attachment(&existing_tag);
+        aliases = existing_tag.aliases;
+    }
 
-            let tag = Tag {
-                listener: listener.to_lowercase().trim().to_owned(),
-                response: response.trim().to_owned(),
-                creator_name: command.user.name.clone(),
-                creator_id: command.user.id.0,
-            };
+    tags.insert(Tag {
+        listener,
+        response,
+        creator_name: command.user.name.clone(),
+        creator_id: command.user.id.0,
+        created_at: Timestamp::now().unix_timestamp(),
+        edited_at: None,
+        trigger_count: 0,
+        embed,
+        attachment,
+        aliases,
+    });
+    save_tags_to_file(&tags);
 
-            for tag in tags.as_ref().clone().iter() {
-                if &tag.listener == listener {
-                    tags.remove(&tag);
-                }
-            }
-
-            tags.insert(tag);
-            save_tags_to_file(&tags);
-            command
-                .create_interaction_response(&ctx.http, |r| {
-                    r.interaction_response_data(|d| d.content("Set tag"))
-                })
-                .await
-                .expect("Error creating interaction response");
-            return;
-        }
-    }
     command
-        .create_interaction_response(&ctx.http, |r| {
-            r.interaction_response_data(|d| d.content("Couldn't set tag"))
-        })
+        .edit_original_interaction_response(&ctx.http, |r| r.content("Set tag"))
         .await
         .expect("Error creating interaction response");
+
+    log_event(ctx, command.guild_id, &command.user, event).await;
 }
 
-pub async fn blacklist_user_from_tags_command(
+/// Explains why the existing tag can't be overwritten by a new tag with this listener, if it can't
+async fn overwrite_rejection(
     ctx: &Context,
-    user: &User,
     command: &ApplicationCommandInteraction,
-) {
-    let users_blacklisted_from_tags = get_tags_blacklisted_users_lock(&ctx.data).await;
+    existing_tag: &Tag,
+    listener: &str,
+) -> Option<String> {
+    if existing_tag.listener != listener {
+        return Some(format!(
+            "{listener} is an alias of the tag {}, remove the alias first",
+            existing_tag.listener
+        ));
+    }
 
-    let response = if users_blacklisted_from_tags.contains(&user.id.0) {
-        users_blacklisted_from_tags.remove(&user.id.0);
-        save_user_tag_blacklist_to_file(&users_blacklisted_from_tags);
+    if !can_manage_tag(ctx, command, existing_tag).await {
+        return Some("That tag already exists and only its creator, tag managers and administrators can overwrite it".to_owned());
+    }
 
-        format!("Removed {} from the blacklist", &user.name)
-    } else {
-        users_blacklisted_from_tags.insert(user.id.0);
-        save_user_tag_blacklist_to_file(&users_blacklisted_from_tags);
+    None
+}
 
-        format!("Added {} to the tag blacklist", &user.name)
+/// Changes the response of a tag while keeping its creator and usage statistics
+pub async fn edit_tag(ctx: &Context, command: &ApplicationCommandInteraction) {
+    let (
+        Some(CommandDataOptionValue::String(listener)),
+        Some(CommandDataOptionValue::String(response)),
+    ) = (get_option(command, "tag"), get_option(command, "response"))
+    else {
+        respond_with_content(ctx, command, "Couldn't edit the tag").await;
+        return;
     };
 
-    command
-        .create_interaction_response(&ctx.http, |r| {
-            r.interaction_response_data(|d| d.content(response))
-        })
-        .await
-        .expect("Error creating interaction response");
-}
+    let tags = get_tags_lock(&ctx.data).await;
 
-pub async fn blacklist_user(ctx: &Context, user: &User) -> String {
-    let users_blacklisted_from_tags = get_tags_blacklisted_users_lock(&ctx.data).await;
+    let Some(mut tag) = find_tag(&tags, listener) else {
+        respond_with_content(ctx, command, "Couldn't find the tag").await;
+        return;
+    };
 
-    if users_blacklisted_from_tags.contains(&user.id.0) {
-        users_blacklisted_from_tags.remove(&user.id.0);
-        save_user_tag_blacklist_to_file(&users_blacklisted_from_tags);
-        format!("Removed {} from the blacklist", &user.name)
-    } else {
-        users_blacklisted_from_tags.insert(user.id.0);
-        save_user_tag_blacklist_to_file(&users_blacklisted_from_tags);
-        format!("Added {} to the tag blacklist", &user.name)
+    if let Err(why) = check_tag(
+        ctx,
+        command,
+        &tag.listener,
+        response,
+        tag.embed.as_ref(),
+        tag.attachment.is_some(),
+    )
+    .await
+    {
+        respond_with_content(ctx, command, format!("Couldn't edit the tag, {why}")).await;
+        return;
+    }
+
+    if !can_manage_tag(ctx, command, &tag).await {
+        respond_with_content(
+            ctx,
+            command,
+            "Only the creator of the tag, tag managers and administrators can edit it",
+        )
+        .await;
+        return;
     }
+
+    tags.remove(&tag);
+    let old_response = std::mem::replace(&mut tag.response, response.trim().to_owned());
+    tag.edited_at = Some(Timestamp::now().unix_timestamp());
+    tags.insert(tag.clone());
+    save_tags_to_file(&tags);
+
+    println!("{} edited tag {}", command.user.name, tag.listener);
+    respond_with_content(ctx, command, "Edited the tag").await;
+
+    log_event(
+        ctx,
+        command.guild_id,
+        &command.user,
+        AuditLogEvent::TagEdited {
+            listener: tag.listener,
+            old_response,
+            new_response: tag.response,
+        },
+    )
+    .await;
+}
+
+/// Checks the tag against the settings of the guild the command was used in and logs it if it's rejected
+async fn check_tag(
+    ctx: &Context,
+    command: &ApplicationCommandInteraction,
+    listener: &str,
+    response: &str,
+    embed: Option<&TagEmbed>,
+    has_attachment: bool,
+) -> Result<(), TagRejection> {
+    let settings = get_guild_tag_settings(ctx, command.guild_id).await;
+    let result = validate_tag(listener, response, embed, has_attachment, &settings);
+
+    if let Err(why) = &result {
+        log_event(
+            ctx,
+            command.guild_id,
+            &command.user,
+            AuditLogEvent::TagRejected {
+                listener: listener.to_owned(),
+                reason: why.to_string(),
+            },
+        )
+        .await;
+    }
+
+    result
+}
+
+/// Finds the tag with the given listener or alias
+fn find_tag(tags: &DashSet<Tag>, listener: &str) -> Option<Tag> {
+    let listener = listener.to_lowercase();
+    let listener = listener.trim();
+
+    tags.iter()
+        .find(|tag| tag.names().any(|name| name == listener))
+        .map(|tag| tag.clone())
 }
 
 /// Checks for all the tag [`Listeners`][L] in the message
 ///
-/// If a [`Listener`][L] is found it returns the response for that [`Listener`][L]
+/// If a [`Listener`][L] is found it returns the [`Tag`] for that [`Listener`][L]
 ///
 /// [L]: self::global_data::Listener
 pub async fn check_for_tag_listeners(
     ctx: &Context,
     words_in_message: &[String],
     user_id: UserId,
-) -> Option<String> {
+) -> Option<Tag> {
     let tags = get_tags_lock(&ctx.data).await;
-    let tag_blacklisted_users = get_tags_blacklisted_users_lock(&ctx.data).await;
+    let tag_ping_preferences = get_tag_ping_preferences_lock(&ctx.data).await;
 
-    if tag_blacklisted_users.contains(&user_id.0) {
+    if tag_ping_preferences
+        .get(&user_id.0)
+        .is_some_and(|preference| *preference == TagPingPreference::Ignore)
+    {
         return None;
     }
 
+    find_tag_in_message(&tags, words_in_message)
+}
+
+/// Counts a trigger of the tag that was responded to, the counts are saved in batches
+pub async fn count_tag_trigger(ctx: &Context, tag: &Tag) {
+    let pending_tag_triggers = get_pending_tag_triggers_lock(&ctx.data).await;
+    *pending_tag_triggers
+        .entry(tag.listener.clone())
+        .or_default() += 1;
+
+    if pending_tag_triggers.iter().map(|count| *count).sum::<u64>() >= TAG_TRIGGER_BATCH_SIZE {
+        save_pending_tag_triggers(&*get_tags_lock(&ctx.data).await, &pending_tag_triggers);
+    }
+}
+
+/// Saves the triggers that haven't reached a full batch yet, used on a timer and when the bot shuts down
+pub async fn flush_pending_tag_triggers(data: &Arc<RwLock<TypeMap>>) {
+    let pending_tag_triggers = get_pending_tag_triggers_lock(data).await;
+
+    if !pending_tag_triggers.is_empty() {
+        save_pending_tag_triggers(&*get_tags_lock(data).await, &pending_tag_triggers);
+    }
+}
+
+/// Saves the pending triggers every [`TAG_TRIGGER_FLUSH_INTERVAL`] so a crash loses at most that much
+pub fn flush_tag_triggers_periodically(data: Arc<RwLock<TypeMap>>) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(TAG_TRIGGER_FLUSH_INTERVAL);
+        loop {
+            interval.tick().await;
+            flush_pending_tag_triggers(&data).await;
+        }
+    });
+}
+
+fn find_tag_in_message(tags: &DashSet<Tag>, words_in_message: &[String]) -> Option<Tag> {
     for tag in tags.iter() {
-        let listener = &tag.listener;
-        let response = &tag.response;
-
-        let listener_words = listener
-            .split(' ')
-            .map(ToString::to_string)
-            .collect::<Vec<String>>();
-
-        let mut listener_iterator = listener_words.iter();
-
-        if listener_words.len() > 1 {
-            let mut count = 0;
-            for message_word in words_in_message.iter() {
-                if message_word == listener_iterator.next()? {
-                    count += 1;
-                } else {
-                    count = 0;
-                    listener_iterator = listener_words.iter();
-                }
+        for listener in tag.names() {
+            let listener_words = listener
+                .split(' ')
+                .map(ToString::to_string)
+                .collect::<Vec<String>>();
+
+            let mut listener_iterator = listener_words.iter();
+
+            if listener_words.len() > 1 {
+                let mut count = 0;
+                for message_word in words_in_message {
+                    if message_word == listener_iterator.next()? {
+                        count += 1;
+                    } else {
+                        count = 0;
+                        listener_iterator = listener_words.iter();
+                    }
 
-                if count == listener_words.len() {
-                    return Some(response.clone());
+                    if count == listener_words.len() {
+                        return Some(tag.clone());
+                    }
                 }
             }
         }
     }
 
     for tag in tags.iter() {
-        let listener = &tag.listener;
-        let response = &tag.response;
+        for listener in tag.names() {
+            let listener_words = listener.split(' ').map(ToString::to_string);
 
-        let listener_words = listener.split(' ').map(ToString::to_string);
-
-        if words_in_message.contains(listener) && listener_words.count() < 2 {
-            return Some(response.clone());
+            if words_in_message.contains(listener) && listener_words.count() < 2 {
+                return Some(tag.clone());
+            }
         }
     }
 
     None
 }
 
-pub async fn set_tag_response_channel(ctx: &Context, command: &ApplicationCommandInteraction) {
-    let guild_id = if let Some(guild_id) = command.guild_id {
-        guild_id
-    } else {
-        command
-            .create_interaction_response(&ctx.http, |r| {
-                r.interaction_response_data(|d| {
-                    d.content("You can only use this command in a server")
-                })
-            })
-            .await
-            .expect("Error creating interaction response");
-        return;
-    };
+/// Adds the pending triggers to their tags and saves them
+fn save_pending_tag_triggers(tags: &DashSet<Tag>, pending_tag_triggers: &DashMap<String, u64>) {
+    let listeners: Vec<String> = pending_tag_triggers
+        .iter()
+        .map(|entry| entry.key().clone())
+        .collect();
+
+    for listener in listeners {
+        let Some((_, count)) = pending_tag_triggers.remove(&listener) else {
+            continue;
+        };
+
+        if let Some(mut tag) = find_tag(tags, &listener) {
+            tags.remove(&tag);
+            tag.trigger_count += count;
+            tags.insert(tag);
+        }
+    }
 
-    let member = command.member.as_ref().expect("Expected member");
-    let member_perms = member.permissions.expect("Couldn't get member permissions");
+    save_tags_to_file(tags);
+}
 
-    let response;
-    if !member_perms.administrator()
-        && member.user.id
-            != ctx
-                .http
-                .get_current_application_info()
-                .await
-                .expect("Couldn't fetch the owner id")
-                .owner
-                .id
-    {
-        response = "You need to have the Administrator permission to invoke this command";
-        command
-            .create_interaction_response(&ctx.http, |r| {
-                r.interaction_response_data(|d| d.content(response))
-            })
-            .await
-            .expect("Error creating interaction response");
+pub async fn set_tag_response_channel(ctx: &Context, command: &ApplicationCommandInteraction) {
+    let Some(guild_id) = guild_id_if_admin(ctx, command).await else {
         return;
-    }
+    };
 
     let channel_id = command.channel_id.0;
     let bot_channel_ids = get_tag_response_channel_id_lock(&ctx.data).await;
     bot_channel_ids.insert(guild_id.0, channel_id);
 
-    response = match save_tag_response_channel(&bot_channel_ids) {
-        Ok(_) => "Successfully set this channel as the tag response channel",
-        Err(_) => "Something went wrong setting the tag response channel",
+    let saved = save_tag_response_channel(&bot_channel_ids).is_ok();
+    let response = if saved {
+        "Successfully set this channel as the tag response channel"
+    } else {
+        "Something went wrong setting the tag response channel"
     };
 
     command
@@ -330,6 +519,41 @@ pub async fn set_tag_response_channel(ctx: &Context, command: &ApplicationComman
         })
         .await
         .expect("Error creating interaction response");
+
+    if saved {
+        log_setting_change(
+            ctx,
+            command,
+            format!("Set <#{channel_id}> as the tag response channel"),
+        )
+        .await;
+    }
+}
+
+pub async fn unset_tag_response_channel(ctx: &Context, command: &ApplicationCommandInteraction) {
+    let Some(guild_id) = guild_id_if_admin(ctx, command).await else {
+        return;
+    };
+
+    let bot_channel_ids = get_tag_response_channel_id_lock(&ctx.data).await;
+
+    if bot_channel_ids.remove(&guild_id.0).is_none() {
+        respond_with_content(ctx, command, "There is no tag response channel set").await;
+        return;
+    }
+
+    let saved = save_tag_response_channel(&bot_channel_ids).is_ok();
+    let response = if saved {
+        "Tags will be responded to in the channel they were tripped off in"
+    } else {
+        "Something went wrong removing the tag response channel"
+    };
+
+    respond_with_content(ctx, command, response).await;
+
+    if saved {
+        log_setting_change(ctx, command, response).await;
+    }
 }
 
 /// It first checks if a tag response channel exists for the guild the message is in.
@@ -339,7 +563,13 @@ pub async fn set_tag_response_channel(ctx: &Context, command: &ApplicationComman
 /// If there is no tag response channel set then it first tries to send a message in the same channel.
 /// If that fails then it sends the message to the tag response channel if one is set
 /// If that fails then it iterates through every channel in the guild until it finds one it can send a message in
-pub async fn respond_to_tag(ctx: &Context, msg: &Message, message: &str) {
+///
+/// Responses sent to another channel mention the user according to their [`TagPingPreference`].
+/// If the guild enabled replies the response replies to the message, or links to it from another channel
+pub async fn respond_to_tag(ctx: &Context, msg: &Message, tag: &Tag) {
+    let ping_preference = get_tag_ping_preference(ctx, msg.author.id).await;
+    let reply_mode = get_tag_reply_mode(ctx, msg.guild_id, ping_preference).await;
+
     let tag_response_channels = get_tag_response_channel_id_lock(&ctx.data).await;
     let tag_response_channel_id =
         tag_response_channels.get(&msg.guild_id.expect("Couldn't get the guild id").0);
@@ -364,27 +594,17 @@ pub async fn respond_to_tag(ctx: &Context, msg: &Message, message: &str) {
         if let Some(tag_response_channel) = tag_response_channel {
             tag_response_channel
                 .send_message(&ctx.http, |m| {
-                    let response_content = if msg.channel_id == tag_response_channel.id {
-                        message.to_owned()
+                    if msg.channel_id == tag_response_channel.id {
+                        reply_mode.reply(add_tag_response(m, tag, tag.response.clone()), msg)
                     } else {
-                        // Create this button only if the user is pinged
-                        if rand::random::<f32>() < 0.05 {
-                            m.components(|c| {
-                                c.create_action_row(|a| {
-                                    a.create_button(|b| {
-                                        b.label("Stop pinging me")
-                                            .style(ButtonStyle::Primary)
-                                            .custom_id(ButtonIds::BlacklistMeFromTags)
-                                    })
-                                })
-                            });
-                        }
-
-                        msg.author.mention().to_string() + " " + message
-                    };
-
-                    m.allowed_mentions(|m| m.parse(ParseValue::Users))
-                        .content(response_content)
+                        add_mentioning_tag_response(
+                            m,
+                            tag,
+                            msg,
+                            ping_preference,
+                            reply_mode.enabled,
+                        )
+                    }
                 })
                 .await
                 .expect("Couldn't send message");
@@ -393,7 +613,14 @@ pub async fn respond_to_tag(ctx: &Context, msg: &Message, message: &str) {
     }
 
     //Try sending a message to the channel the tag listener was tripped off
-    if msg.channel_id.say(&ctx.http, message).await.is_err() {
+    if msg
+        .channel_id
+        .send_message(&ctx.http, |m| {
+            reply_mode.reply(add_tag_response(m, tag, tag.response.clone()), msg)
+        })
+        .await
+        .is_err()
+    {
         //If sending a message fails iterate through the guild channels until it manages to send a message
         let channels: Vec<Channel> = msg
             .guild(&ctx.cache)
@@ -406,17 +633,7 @@ pub async fn respond_to_tag(ctx: &Context, msg: &Message, message: &str) {
             match channel
                 .id()
                 .send_message(&ctx.http, |m| {
-                    m.components(|c| {
-                        c.create_action_row(|a| {
-                            a.create_button(|b| {
-                                b.label("Stop pinging me")
-                                    .style(ButtonStyle::Primary)
-                                    .custom_id(ButtonIds::BlacklistMeFromTags)
-                            })
-                        })
-                    })
-                    .allowed_mentions(|m| m.parse(ParseValue::Users))
-                    .content(msg.author.mention().to_string() + " " + message)
+                    add_mentioning_tag_response(m, tag, msg, ping_preference, reply_mode.enabled)
                 })
                 .await
             {
@@ -431,14 +648,21 @@ pub fn init_tags_data(mut data: RwLockWriteGuard<TypeMap>) -> Result<(), Box<dyn
     let tags: DashSet<Tag> = serde_json::from_str(&fs::read_to_string(create_file_if_missing(
         TAG_PATH, "[]",
     )?)?)?;
-    let user_tag_blacklist: DashSet<u64> = serde_json::from_str(&fs::read_to_string(
-        create_file_if_missing(BLACKLISTED_USERS_PATH, "[]")?,
-    )?)?;
+    let tag_ping_preferences: DashMap<u64, TagPingPreference> = serde_json::from_str(
+        &fs::read_to_string(create_file_if_missing(TAG_PING_PREFERENCES_PATH, "{}")?)?,
+    )?;
+    migrate_tag_blacklist(&tag_ping_preferences)?;
     let bot_channel: DashMap<u64, u64> = serde_json::from_str(&fs::read_to_string(
         create_file_if_missing(BOT_CHANNEL_PATH, "{}")?,
     )?)?;
+    let tag_settings: DashMap<u64, TagSettings> = serde_json::from_str(&fs::read_to_string(
+        create_file_if_missing(TAG_SETTINGS_PATH, "{}")?,
+    )?)?;
     data.insert::<TagsContainer>(Arc::new(tags));
-    data.insert::<TagBlacklistedUsers>(Arc::new(user_tag_blacklist));
+    data.insert::<TagPingPreferences>(Arc::new(tag_ping_preferences));
     data.insert::<TagResponseChannelIds>(Arc::new(bot_channel));
+    data.insert::<TagSettingsContainer>(Arc::new(tag_settings));
+    data.insert::<TagCooldowns>(Arc::new(TagCooldowns::default()));
+    data.insert::<PendingTagTriggers>(Arc::new(DashMap::new()));
     Ok(())
 }
@@ -0,0 +1,213 @@
+use super::{
+    file_operations::save_tag_settings, global_data::get_tag_settings_lock,
+    validation::DEFAULT_TAG_STOPWORDS, TagSettings,
+};
+use crate::client::{
+    audit_log::log_setting_change,
+    helper_funcs::{get_option, guild_id_if_admin, respond_with_content},
+};
+use serenity::{
+    client::Context,
+    model::prelude::interaction::application_command::{
+        ApplicationCommandInteraction, CommandDataOptionValue,
+    },
+    utils::Colour,
+};
+use std::collections::HashSet;
+
+/// Changes what content tags can have in the guild
+pub async fn tag_moderation(ctx: &Context, command: &ApplicationCommandInteraction) {
+    let Some(guild_id) = guild_id_if_admin(ctx, command).await else {
+        return;
+    };
+
+    let Some(subcommand) = command.data.options.first() else {
+        respond_with_content(ctx, command, "Something went wrong").await;
+        return;
+    };
+
+    if subcommand.name == "show" {
+        show_tag_moderation(ctx, command).await;
+        return;
+    }
+
+    let tag_settings = get_tag_settings_lock(&ctx.data).await;
+    let response = {
+        let mut settings = tag_settings.entry(guild_id.0).or_default();
+        match subcommand.name.as_str() {
+            "max-length" => set_max_lengths(command, &mut settings),
+            "ban-word" => get_word_option(command).map(|word| {
+                settings.banned_words.insert(word.clone());
+                format!("{word} can no longer be used in tags")
+            }),
+            "unban-word" => get_word_option(command).map(|word| {
+                settings.banned_words.remove(&word);
+                format!("{word} can be used in tags again")
+            }),
+            "block-invites" => match get_option(command, "enabled") {
+                Some(CommandDataOptionValue::Boolean(true)) => {
+                    settings.block_invites = true;
+                    Some("Tags can no longer contain invite links".to_owned())
+                }
+                Some(CommandDataOptionValue::Boolean(false)) => {
+                    settings.block_invites = false;
+                    Some("Tags can contain invite links again".to_owned())
+                }
+                _ => None,
+            },
+            "add-stopword" => get_word_option(command).map(|word| {
+                get_stopwords(&mut settings).insert(word.clone());
+                format!("{word} can no longer be a tag on its own")
+            }),
+            "remove-stopword" => get_word_option(command).map(|word| {
+                get_stopwords(&mut settings).remove(&word);
+                format!("{word} can be a tag again")
+            }),
+            _ => None,
+        }
+    };
+
+    let Some(response) = response else {
+        respond_with_content(ctx, command, "Something went wrong").await;
+        return;
+    };
+
+    if save_tag_settings(&tag_settings).is_err() {
+        respond_with_content(
+            ctx,
+            command,
+            "Something went wrong while saving the tag moderation settings",
+        )
+        .await;
+        return;
+    }
+
+    respond_with_content(ctx, command, &response).await;
+    log_setting_change(ctx, command, response).await;
+}
+
+/// Sets the maximum listener and response lengths that were given, 0 removes the limit
+fn set_max_lengths(
+    command: &ApplicationCommandInteraction,
+    settings: &mut TagSettings,
+) -> Option<String> {
+    let get_length_option = |name| match get_option(command, name) {
+        Some(CommandDataOptionValue::Integer(length)) => usize::try_from(*length)
+            .ok()
+            .map(|length| (length > 0).then_some(length)),
+        _ => None,
+    };
+
+    let listener_length = get_length_option("listener");
+    let response_length = get_length_option("response");
+
+    if listener_length.is_none() && response_length.is_none() {
+        return None;
+    }
+
+    if let Some(length) = listener_length {
+        settings.max_listener_length = length;
+    }
+    if let Some(length) = response_length {
+        settings.max_response_length = length;
+    }
+
+    Some(format!(
+        "Tags can be {} long and responses can be {} long",
+        describe_length(settings.max_listener_length),
+        describe_length(settings.max_response_length)
+    ))
+}
+
+fn describe_length(length: Option<usize>) -> String {
+    length.map_or_else(
+        || "any number of characters".to_owned(),
+        |length| format!("at most {length} characters"),
+    )
+}
+
+fn get_word_option(command: &ApplicationCommandInteraction) -> Option<String> {
+    match get_option(command, "word")? {
+        CommandDataOptionValue::String(word) => Some(word.trim().to_lowercase()),
+        _ => None,
+    }
+    .filter(|word| !word.is_empty())
+}
+
+/// The stopwords of the guild, starting from the default ones if it hasn't changed them yet
+fn get_stopwords(settings: &mut TagSettings) -> &mut HashSet<String> {
+    settings.stopwords.get_or_insert_with(|| {
+        DEFAULT_TAG_STOPWORDS
+            .iter()
+            .map(|word| (*word).to_owned())
+            .collect()
+    })
+}
+
+async fn show_tag_moderation(ctx: &Context, command: &ApplicationCommandInteraction) {
+    let settings = get_tag_settings_lock(&ctx.data)
+        .await
+        .get(&command.guild_id.expect("Only used in guilds").0)
+        .map(|settings| settings.clone())
+        .unwrap_or_default();
+
+    let mut banned_words: Vec<&String> = settings.banned_words.iter().collect();
+    banned_words.sort();
+    let banned_words = if banned_words.is_empty() {
+        "None".to_owned()
+    } else {
+        format!("||{}||", join(&banned_words))
+    };
+
+    let stopwords = match &settings.stopwords {
+        Some(stopwords) => {
+            let mut stopwords: Vec<&String> = stopwords.iter().collect();
+            stopwords.sort();
+            join(&stopwords)
+        }
+        None => DEFAULT_TAG_STOPWORDS.join(", "),
+    };
+    let colour = Colour::from_rgb(149, 8, 2);
+
+    command
+        .create_interaction_response(&ctx.http, |r| {
+            r.interaction_response_data(|d| {
+                d.embed(|e| {
+                    e.title("Tag moderation")
+                        .colour(colour)
+                        .field(
+                            "Tag length",
+                            describe_length(settings.max_listener_length),
+                            true,
+                        )
+                        .field(
+                            "Response length",
+                            describe_length(settings.max_response_length),
+                            true,
+                        )
+                        .field(
+                            "Invite links",
+                            if settings.block_invites {
+                                "Blocked"
+                            } else {
+                                "Allowed"
+                            },
+                            true,
+                        )
+                        .field("Banned words", banned_words, false)
+                        .field("Stopwords", stopwords, false)
+                })
+                .ephemeral(true)
+            })
+        })
+        .await
+        .expect("Error creating interaction response");
+}
+
+fn join(words: &[&String]) -> String {
+    words
+        .iter()
+        .map(|word| word.as_str())
+        .collect::<Vec<&str>>()
+        .join(", ")
+}
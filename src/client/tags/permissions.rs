@@ -0,0 +1,194 @@
+use super::{
+    file_operations::{save_tag_settings, save_tags_to_file},
+    find_tag,
+    global_data::{get_tag_settings_lock, get_tags_lock},
+    Tag,
+};
+use crate::client::{
+    audit_log::{log_event, log_setting_change, AuditLogEvent},
+    helper_funcs::{
+        get_option, guild_id_if_admin, is_admin_or_owner, member_has_role, respond_with_content,
+    },
+};
+use serenity::{
+    client::Context,
+    model::prelude::interaction::application_command::{
+        ApplicationCommandInteraction, CommandDataOptionValue,
+    },
+};
+
+/// Checks if the user who invoked the command can edit, remove or transfer the tag.
+///
+/// That's the creator of the tag, anyone with the guild's tag manager role and administrators
+pub async fn can_manage_tag(
+    ctx: &Context,
+    command: &ApplicationCommandInteraction,
+    tag: &Tag,
+) -> bool {
+    tag.creator_id == command.user.id.0
+        || has_tag_manager_role(ctx, command).await
+        || is_admin_or_owner(ctx, command).await
+}
+
+/// Checks if the user who invoked the command is allowed to create new tags.
+///
+/// If the guild doesn't restrict tag creation to a role everyone is.
+pub async fn can_create_tags(ctx: &Context, command: &ApplicationCommandInteraction) -> bool {
+    let Some(guild_id) = command.guild_id else {
+        return true;
+    };
+
+    let creator_role = get_tag_settings_lock(&ctx.data)
+        .await
+        .get(&guild_id.0)
+        .and_then(|settings| settings.creator_role);
+
+    let Some(creator_role) = creator_role else {
+        return true;
+    };
+
+    member_has_role(command, creator_role)
+        || has_tag_manager_role(ctx, command).await
+        || is_admin_or_owner(ctx, command).await
+}
+
+async fn has_tag_manager_role(ctx: &Context, command: &ApplicationCommandInteraction) -> bool {
+    let Some(guild_id) = command.guild_id else {
+        return false;
+    };
+
+    let manager_role = get_tag_settings_lock(&ctx.data)
+        .await
+        .get(&guild_id.0)
+        .and_then(|settings| settings.manager_role);
+
+    manager_role.is_some_and(|role_id| member_has_role(command, role_id))
+}
+
+/// Gives the ownership of a tag to another user
+pub async fn transfer_tag(ctx: &Context, command: &ApplicationCommandInteraction) {
+    let (
+        Some(CommandDataOptionValue::String(listener)),
+        Some(CommandDataOptionValue::User(new_owner, _)),
+    ) = (get_option(command, "tag"), get_option(command, "user"))
+    else {
+        respond_with_content(ctx, command, "Something went wrong").await;
+        return;
+    };
+
+    let tags = get_tags_lock(&ctx.data).await;
+
+    let Some(mut tag) = find_tag(&tags, listener) else {
+        respond_with_content(ctx, command, "Couldn't find the tag").await;
+        return;
+    };
+
+    if !can_manage_tag(ctx, command, &tag).await {
+        respond_with_content(
+            ctx,
+            command,
+            "Only the creator of the tag, tag managers and administrators can transfer it",
+        )
+        .await;
+        return;
+    }
+
+    tags.remove(&tag);
+    tag.creator_id = new_owner.id.0;
+    tag.creator_name = new_owner.name.clone();
+    tags.insert(tag.clone());
+    save_tags_to_file(&tags);
+
+    println!(
+        "{} transferred tag {} to {}",
+        command.user.name, tag.listener, new_owner.name
+    );
+    respond_with_content(
+        ctx,
+        command,
+        format!("{} now owns the tag {}", new_owner.name, tag.listener),
+    )
+    .await;
+
+    log_event(
+        ctx,
+        command.guild_id,
+        &command.user,
+        AuditLogEvent::TagTransferred {
+            listener: tag.listener,
+            new_owner: new_owner.id,
+        },
+    )
+    .await;
+}
+
+/// Sets the role that can edit and remove every tag, or removes it if no role is given
+pub async fn set_tag_manager_role(ctx: &Context, command: &ApplicationCommandInteraction) {
+    let Some(guild_id) = guild_id_if_admin(ctx, command).await else {
+        return;
+    };
+
+    let role = get_role_option(command);
+
+    let tag_settings = get_tag_settings_lock(&ctx.data).await;
+    tag_settings.entry(guild_id.0).or_default().manager_role = role;
+
+    let saved = save_tag_settings(&tag_settings).is_ok();
+    let response = match (saved, role) {
+        (true, Some(role)) => format!("<@&{role}> can now manage every tag"),
+        (true, None) => "Removed the tag manager role".to_owned(),
+        (false, _) => "Something went wrong while setting the tag manager role".to_owned(),
+    };
+
+    command
+        .create_interaction_response(&ctx.http, |r| {
+            r.interaction_response_data(|d| {
+                d.content(&response).allowed_mentions(|m| m.empty_parse())
+            })
+        })
+        .await
+        .expect("Error creating interaction response");
+
+    if saved {
+        log_setting_change(ctx, command, response).await;
+    }
+}
+
+/// Restricts creating tags to a role, or lets everyone create them again if no role is given
+pub async fn set_tag_creator_role(ctx: &Context, command: &ApplicationCommandInteraction) {
+    let Some(guild_id) = guild_id_if_admin(ctx, command).await else {
+        return;
+    };
+
+    let role = get_role_option(command);
+
+    let tag_settings = get_tag_settings_lock(&ctx.data).await;
+    tag_settings.entry(guild_id.0).or_default().creator_role = role;
+
+    let saved = save_tag_settings(&tag_settings).is_ok();
+    let response = match (saved, role) {
+        (true, Some(role)) => format!("Only <@&{role}> can create tags now"),
+        (true, None) => "Everyone can create tags now".to_owned(),
+        (false, _) => "Something went wrong while setting the tag creator role".to_owned(),
+    };
+
+    command
+        .create_interaction_response(&ctx.http, |r| {
+            r.interaction_response_data(|d| {
+                d.content(&response).allowed_mentions(|m| m.empty_parse())
+            })
+        })
+        .await
+        .expect("Error creating interaction response");
+
+    if saved {
+        log_setting_change(ctx, command, response).await;
+    }
+}
+
+fn get_role_option(command: &ApplicationCommandInteraction) -> Option<u64> {
+    match get_option(command, "role")? {
+        CommandDataOptionValue::Role(role) => Some(role.id.0),
+        _ => None,
+    }
+}
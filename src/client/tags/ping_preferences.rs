@@ -0,0 +1,222 @@
+use super::{
+    assets::add_tag_response,
+    file_operations::save_tag_ping_preferences,
+    global_data::{get_tag_ping_preferences_lock, TagPingPreference, BLACKLISTED_USERS_PATH},
+    Tag,
+};
+use crate::client::{
+    helper_funcs::{get_option, respond_with_content},
+    ButtonIds,
+};
+use dashmap::{DashMap, DashSet};
+use serenity::{
+    builder::{CreateComponents, CreateMessage, ParseValue},
+    client::Context,
+    model::{
+        channel::Message,
+        id::UserId,
+        prelude::{
+            component::ButtonStyle,
+            interaction::{
+                application_command::{ApplicationCommandInteraction, CommandDataOptionValue},
+                message_component::MessageComponentInteraction,
+                InteractionResponseType, MessageFlags,
+            },
+            User,
+        },
+    },
+    prelude::Mentionable,
+};
+use std::{error::Error, fs, path::Path, str::FromStr};
+
+impl TagPingPreference {
+    fn description(self) -> &'static str {
+        match self {
+            Self::Ping => "Ping me",
+            Self::NoPing => "Reply without pinging me",
+            Self::Ignore => "Don't respond to me",
+        }
+    }
+
+    fn button_id(self) -> ButtonIds {
+        match self {
+            Self::Ping => ButtonIds::TagPingMe,
+            Self::NoPing => ButtonIds::TagPingNoPing,
+            Self::Ignore => ButtonIds::TagPingIgnore,
+        }
+    }
+}
+
+pub async fn get_tag_ping_preference(ctx: &Context, user_id: UserId) -> TagPingPreference {
+    get_tag_ping_preferences_lock(&ctx.data)
+        .await
+        .get(&user_id.0)
+        .map(|preference| *preference)
+        .unwrap_or_default()
+}
+
+/// Sets how the bot responds to the user when they trip off a tag
+pub async fn set_tag_ping_preference(ctx: &Context, command: &ApplicationCommandInteraction) {
+    let Some(CommandDataOptionValue::String(preference)) = get_option(command, "preference") else {
+        respond_with_content(ctx, command, "Something went wrong").await;
+        return;
+    };
+    let Ok(preference) = TagPingPreference::from_str(preference) else {
+        respond_with_content(ctx, command, "Something went wrong").await;
+        return;
+    };
+
+    let response = save_preference(ctx, &command.user, preference).await;
+
+    command
+        .create_interaction_response(&ctx.http, |r| {
+            r.interaction_response_data(|d| d.content(response).flags(MessageFlags::EPHEMERAL))
+        })
+        .await
+        .expect("Error creating interaction response");
+}
+
+/// Shows the user their ping preference with buttons to change it
+pub async fn show_tag_ping_settings(ctx: &Context, button: &MessageComponentInteraction) {
+    let preference = get_tag_ping_preference(ctx, button.user.id).await;
+
+    button
+        .create_interaction_response(&ctx.http, |r| {
+            r.interaction_response_data(|d| {
+                d.content("How should I respond when you trip off a tag?")
+                    .components(|c| create_preference_buttons(c, preference))
+                    .flags(MessageFlags::EPHEMERAL)
+            })
+        })
+        .await
+        .expect("Error creating interaction response");
+}
+
+/// Saves the preference of the button that was pressed in the ping settings
+pub async fn set_tag_ping_preference_button(
+    ctx: &Context,
+    button: &MessageComponentInteraction,
+    button_id: &ButtonIds,
+) {
+    let preference = match button_id {
+        ButtonIds::TagPingNoPing => TagPingPreference::NoPing,
+        ButtonIds::TagPingIgnore => TagPingPreference::Ignore,
+        _ => TagPingPreference::Ping,
+    };
+
+    let response = save_preference(ctx, &button.user, preference).await;
+
+    button
+        .create_interaction_response(&ctx.http, |r| {
+            r.kind(InteractionResponseType::UpdateMessage)
+                .interaction_response_data(|d| {
+                    d.content(response)
+                        .components(|c| create_preference_buttons(c, preference))
+                })
+        })
+        .await
+        .expect("Error creating interaction response");
+}
+
+async fn save_preference(ctx: &Context, user: &User, preference: TagPingPreference) -> String {
+    let tag_ping_preferences = get_tag_ping_preferences_lock(&ctx.data).await;
+
+    if preference == TagPingPreference::default() {
+        tag_ping_preferences.remove(&user.id.0);
+    } else {
+        tag_ping_preferences.insert(user.id.0, preference);
+    }
+
+    match save_tag_ping_preferences(&tag_ping_preferences) {
+        Ok(()) => format!("Your tag preference is now: {}", preference.description()),
+        Err(_) => "Something went wrong saving your tag preference".to_owned(),
+    }
+}
+
+fn create_preference_buttons(
+    c: &mut CreateComponents,
+    current_preference: TagPingPreference,
+) -> &mut CreateComponents {
+    c.create_action_row(|a| {
+        for preference in [
+            TagPingPreference::Ping,
+            TagPingPreference::NoPing,
+            TagPingPreference::Ignore,
+        ] {
+            let style = if preference == current_preference {
+                ButtonStyle::Success
+            } else {
+                ButtonStyle::Secondary
+            };
+
+            a.create_button(|b| {
+                b.label(preference.description())
+                    .style(style)
+                    .custom_id(preference.button_id())
+            });
+        }
+        a
+    })
+}
+
+/// Adds the tag response with a mention of the user who tripped it off,
+/// and a link to their message if `jump_link` is set.
+///
+/// The user is only pinged if that's their [`TagPingPreference`],
+/// and there's always a button to change it
+pub fn add_mentioning_tag_response<'a, 'b>(
+    m: &'b mut CreateMessage<'a>,
+    tag: &'a Tag,
+    msg: &Message,
+    preference: TagPingPreference,
+    jump_link: bool,
+) -> &'b mut CreateMessage<'a> {
+    m.components(|c| {
+        c.create_action_row(|a| {
+            a.create_button(|b| {
+                b.label("Ping settings")
+                    .style(ButtonStyle::Secondary)
+                    .custom_id(ButtonIds::TagPingSettings)
+            })
+        })
+    });
+
+    if preference == TagPingPreference::Ping {
+        m.allowed_mentions(|am| am.parse(ParseValue::Users));
+    } else {
+        m.allowed_mentions(|am| am.empty_parse());
+    }
+
+    let mut content = msg.author.mention().to_string() + " " + &tag.response;
+    if jump_link {
+        content.push('\n');
+        content.push_str(&msg.link());
+    }
+
+    add_tag_response(m, tag, content)
+}
+
+/// Moves the users of the old tag blacklist to [`TagPingPreference::Ignore`] and deletes the blacklist
+pub fn migrate_tag_blacklist(
+    tag_ping_preferences: &DashMap<u64, TagPingPreference>,
+) -> Result<(), Box<dyn Error>> {
+    if !Path::new(BLACKLISTED_USERS_PATH).exists() {
+        return Ok(());
+    }
+
+    let blacklisted_users: DashSet<u64> =
+        serde_json::from_str(&fs::read_to_string(BLACKLISTED_USERS_PATH)?)?;
+
+    for user_id in blacklisted_users {
+        tag_ping_preferences
+            .entry(user_id)
+            .or_insert(TagPingPreference::Ignore);
+    }
+
+    save_tag_ping_preferences(tag_ping_preferences)?;
+    fs::remove_file(BLACKLISTED_USERS_PATH)?;
+
+    println!("Moved the tag blacklist to the tag ping preferences");
+
+    Ok(())
+}
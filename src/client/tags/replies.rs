@@ -0,0 +1,100 @@
+use super::{
+    file_operations::save_tag_settings, global_data::get_tag_settings_lock, TagPingPreference,
+};
+use crate::client::{
+    audit_log::log_setting_change,
+    helper_funcs::{get_option, guild_id_if_admin, respond_with_content},
+};
+use serenity::{
+    builder::CreateMessage,
+    client::Context,
+    model::{
+        channel::Message,
+        id::GuildId,
+        prelude::interaction::application_command::{
+            ApplicationCommandInteraction, CommandDataOptionValue,
+        },
+    },
+};
+
+/// How tag responses refer to the message that tripped off the tag
+#[derive(Clone, Copy, Default)]
+pub struct TagReplyMode {
+    /// Reply to the message, or link to it if the response is sent to another channel
+    pub enabled: bool,
+    /// If the reply pings the user who tripped off the tag
+    pub ping: bool,
+}
+
+impl TagReplyMode {
+    /// Makes the response a reply to the message if replies are enabled
+    pub fn reply<'a, 'b>(
+        self,
+        m: &'b mut CreateMessage<'a>,
+        msg: &Message,
+    ) -> &'b mut CreateMessage<'a> {
+        if self.enabled {
+            m.reference_message(msg)
+                .allowed_mentions(|am| am.replied_user(self.ping));
+        }
+        m
+    }
+}
+
+/// Gets the reply mode of the guild.
+///
+/// Replies only ping if the guild allows it and it's the [`TagPingPreference`] of the user
+pub async fn get_tag_reply_mode(
+    ctx: &Context,
+    guild_id: Option<GuildId>,
+    ping_preference: TagPingPreference,
+) -> TagReplyMode {
+    let Some(guild_id) = guild_id else {
+        return TagReplyMode::default();
+    };
+
+    get_tag_settings_lock(&ctx.data)
+        .await
+        .get(&guild_id.0)
+        .map(|settings| TagReplyMode {
+            enabled: settings.reply_to_trigger,
+            ping: settings.ping_on_reply && ping_preference == TagPingPreference::Ping,
+        })
+        .unwrap_or_default()
+}
+
+/// Sets if tag responses are replies to the message that tripped off the tag and if those replies ping
+pub async fn set_tag_reply_mode(ctx: &Context, command: &ApplicationCommandInteraction) {
+    let Some(guild_id) = guild_id_if_admin(ctx, command).await else {
+        return;
+    };
+
+    let Some(CommandDataOptionValue::Boolean(enabled)) = get_option(command, "enabled") else {
+        respond_with_content(ctx, command, "Something went wrong").await;
+        return;
+    };
+
+    let tag_settings = get_tag_settings_lock(&ctx.data).await;
+    let ping_on_reply = {
+        let mut settings = tag_settings.entry(guild_id.0).or_default();
+        settings.reply_to_trigger = *enabled;
+        if let Some(CommandDataOptionValue::Boolean(ping)) = get_option(command, "ping") {
+            settings.ping_on_reply = *ping;
+        }
+        settings.ping_on_reply
+    };
+
+    let saved = save_tag_settings(&tag_settings).is_ok();
+    let response = match (saved, enabled, ping_on_reply) {
+        (true, true, true) => "Tag responses are now replies that ping the user",
+        (true, true, false) => "Tag responses are now replies that don't ping the user",
+        (true, false, _) => "Tag responses are no longer replies",
+        (false, _, _) => "Something went wrong while setting the tag reply mode",
+    };
+
+    respond_with_content(ctx, command, &response).await;
+
+    if saved {
+        log_setting_change(ctx, command, response).await;
+    }
+}
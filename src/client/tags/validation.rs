@@ -0,0 +1,297 @@
+use super::global_data::{get_tag_settings_lock, TagEmbed, TagSettings};
+use regex::Regex;
+use serenity::{client::Context, model::id::GuildId};
+use thiserror::Error;
+
+/// Listeners that would trip off a tag in almost every message,
+/// used by guilds that haven't set their own [`stopwords`][TagSettings::stopwords]
+pub const DEFAULT_TAG_STOPWORDS: &[&str] = &[
+    "a", "an", "and", "are", "as", "at", "be", "but", "by", "for", "i", "if", "in", "is", "it",
+    "me", "my", "no", "of", "on", "or", "so", "that", "the", "this", "to", "was", "we", "yes",
+    "you",
+];
+
+/// The reason a tag can't be saved
+#[derive(Debug, Error)]
+pub enum TagRejection {
+    #[error("the tag can't be empty")]
+    EmptyListener,
+    #[error("a tag needs a response, an embed or an attachment")]
+    NoResponse,
+    #[error("can't add a mention")]
+    ContainsMention,
+    #[error("the tag can't be longer than {0} characters")]
+    ListenerTooLong(usize),
+    #[error("the response can't be longer than {0} characters")]
+    ResponseTooLong(usize),
+    #[error("it contains the banned word {0}")]
+    ContainsBannedWord(String),
+    #[error("can't add invite links")]
+    ContainsInvite,
+    #[error("{0} is too common, the tag would be tripped off all the time")]
+    TooCommon(String),
+}
+
+/// Gets the tag settings of the guild, or the default ones outside of guilds
+pub async fn get_guild_tag_settings(ctx: &Context, guild_id: Option<GuildId>) -> TagSettings {
+    let Some(guild_id) = guild_id else {
+        return TagSettings::default();
+    };
+
+    get_tag_settings_lock(&ctx.data)
+        .await
+        .get(&guild_id.0)
+        .map(|settings| settings.clone())
+        .unwrap_or_default()
+}
+
+/// Checks if a tag with this content can be saved in a guild with these settings
+pub fn validate_tag(
+    listener: &str,
+    response: &str,
+    embed: Option<&TagEmbed>,
+    has_attachment: bool,
+    settings: &TagSettings,
+) -> Result<(), TagRejection> {
+    let listener = listener.trim().to_lowercase();
+
+    if listener.is_empty() {
+        return Err(TagRejection::EmptyListener);
+    }
+
+    if response.trim().is_empty() && embed.is_none() && !has_attachment {
+        return Err(TagRejection::NoResponse);
+    }
+
+    if let Some(max_length) = settings.max_listener_length {
+        if listener.chars().count() > max_length {
+            return Err(TagRejection::ListenerTooLong(max_length));
+        }
+    }
+
+    if let Some(max_length) = settings.max_response_length {
+        if response.trim().chars().count() > max_length {
+            return Err(TagRejection::ResponseTooLong(max_length));
+        }
+    }
+
+    let embed_texts = embed.map_or_else(Vec::new, |embed| {
+        [&embed.title, &embed.description, &embed.image_url]
+            .into_iter()
+            .flatten()
+            .map(String::as_str)
+            .collect()
+    });
+    let texts: Vec<&str> = [listener.as_str(), response]
+        .into_iter()
+        .chain(embed_texts)
+        .collect();
+
+    if texts.iter().any(|text| contains_mention(text)) {
+        return Err(TagRejection::ContainsMention);
+    }
+
+    if settings.block_invites && texts.iter().any(|text| contains_invite(text)) {
+        return Err(TagRejection::ContainsInvite);
+    }
+
+    if let Some(banned_word) = settings
+        .banned_words
+        .iter()
+        .find(|banned_word| texts.iter().any(|text| contains_word(text, banned_word)))
+    {
+        return Err(TagRejection::ContainsBannedWord(banned_word.clone()));
+    }
+
+    if is_too_common(&listener, settings) {
+        return Err(TagRejection::TooCommon(listener));
+    }
+
+    Ok(())
+}
+
+/// Checks if the text contains a user or role mention, `@everyone` or `@here`
+fn contains_mention(text: &str) -> bool {
+    let user_regex = Regex::new(r"<@!?(\d+)>").expect("Invalid regular expression");
+    let role_regex = Regex::new(r"<@&(\d+)>").expect("Invalid regular expression");
+
+    user_regex.is_match(text)
+        || role_regex.is_match(text)
+        || text.contains("@everyone")
+        || text.contains("@here")
+}
+
+/// Checks if the text contains a discord invite link
+fn contains_invite(text: &str) -> bool {
+    let invite_regex = Regex::new(r"(?i)(discord\.gg|discord(app)?\.com/invite)/\S+")
+        .expect("Invalid regular expression");
+
+    invite_regex.is_match(text)
+}
+
+/// Checks if the text contains the word on its own, not as part of another word.
+///
+/// Only ends of the word that are letters or numbers need a word boundary,
+/// words like `c++` or `:emoji:` end in characters that already separate them
+fn contains_word(text: &str, word: &str) -> bool {
+    let is_word_char = |c: char| c.is_alphanumeric() || c == '_';
+    let start = if word.starts_with(is_word_char) {
+        r"\b"
+    } else {
+        ""
+    };
+    let end = if word.ends_with(is_word_char) {
+        r"\b"
+    } else {
+        ""
+    };
+
+    let word_regex = Regex::new(&format!(r"(?i){start}{}{end}", regex::escape(word)))
+        .expect("Invalid regular expression");
+
+    word_regex.is_match(text)
+}
+
+/// Checks if every word of the listener is a stopword
+fn is_too_common(listener: &str, settings: &TagSettings) -> bool {
+    listener
+        .split_whitespace()
+        .all(|word| match &settings.stopwords {
+            Some(stopwords) => stopwords.contains(word),
+            None => DEFAULT_TAG_STOPWORDS.contains(&word),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{contains_word, validate_tag, TagRejection};
+    use crate::client::tags::global_data::TagSettings;
+    use std::collections::HashSet;
+
+    fn validate(
+        listener: &str,
+        response: &str,
+        settings: &TagSettings,
+    ) -> Result<(), TagRejection> {
+        validate_tag(listener, response, None, false, settings)
+    }
+
+    #[test]
+    fn normal_tags_are_accepted() {
+        assert!(validate("cat", "meow", &TagSettings::default()).is_ok());
+        assert!(validate("the cat", "meow", &TagSettings::default()).is_ok());
+    }
+
+    #[test]
+    fn empty_tags_are_rejected() {
+        assert!(matches!(
+            validate("  ", "meow", &TagSettings::default()),
+            Err(TagRejection::EmptyListener)
+        ));
+        assert!(matches!(
+            validate("cat", " ", &TagSettings::default()),
+            Err(TagRejection::NoResponse)
+        ));
+    }
+
+    #[test]
+    fn length_limits_are_enforced() {
+        let settings = TagSettings {
+            max_listener_length: Some(3),
+            max_response_length: Some(4),
+            ..Default::default()
+        };
+
+        assert!(validate("cat", "meow", &settings).is_ok());
+        assert!(matches!(
+            validate("cats", "meow", &settings),
+            Err(TagRejection::ListenerTooLong(3))
+        ));
+        assert!(matches!(
+            validate("cat", "meow!", &settings),
+            Err(TagRejection::ResponseTooLong(4))
+        ));
+    }
+
+    #[test]
+    fn mentions_are_rejected() {
+        let settings = TagSettings::default();
+
+        assert!(matches!(
+            validate("cat", "hi <@123>", &settings),
+            Err(TagRejection::ContainsMention)
+        ));
+        assert!(matches!(
+            validate("cat", "hi <@&123>", &settings),
+            Err(TagRejection::ContainsMention)
+        ));
+        assert!(matches!(
+            validate("@everyone", "meow", &settings),
+            Err(TagRejection::ContainsMention)
+        ));
+    }
+
+    #[test]
+    fn invites_are_rejected_when_blocked() {
+        let blocking = TagSettings {
+            block_invites: true,
+            ..Default::default()
+        };
+
+        assert!(matches!(
+            validate("cat", "join discord.gg/abc", &blocking),
+            Err(TagRejection::ContainsInvite)
+        ));
+        assert!(matches!(
+            validate("cat", "https://discord.com/invite/abc", &blocking),
+            Err(TagRejection::ContainsInvite)
+        ));
+        assert!(validate("cat", "join discord.gg/abc", &TagSettings::default()).is_ok());
+    }
+
+    #[test]
+    fn banned_words_are_rejected() {
+        let settings = TagSettings {
+            banned_words: HashSet::from(["dog".to_owned()]),
+            ..Default::default()
+        };
+
+        assert!(matches!(
+            validate("cat", "not a DOG!", &settings),
+            Err(TagRejection::ContainsBannedWord(word)) if word == "dog"
+        ));
+        assert!(validate("cat", "hotdogs", &settings).is_ok());
+    }
+
+    #[test]
+    fn banned_words_can_start_or_end_with_symbols() {
+        assert!(contains_word("you f.u. there", "f.u."));
+        assert!(contains_word("nice :emoji:", ":emoji:"));
+        assert!(contains_word("c++ is fine", "c++"));
+        assert!(!contains_word("abc++", "c++"));
+        assert!(contains_word("Cat.", "cat"));
+        assert!(!contains_word("category", "cat"));
+    }
+
+    #[test]
+    fn stopwords_are_too_common() {
+        assert!(matches!(
+            validate("the", "meow", &TagSettings::default()),
+            Err(TagRejection::TooCommon(listener)) if listener == "the"
+        ));
+        assert!(matches!(
+            validate("and the", "meow", &TagSettings::default()),
+            Err(TagRejection::TooCommon(_))
+        ));
+
+        let settings = TagSettings {
+            stopwords: Some(HashSet::from(["cat".to_owned()])),
+            ..Default::default()
+        };
+        assert!(matches!(
+            validate("cat", "meow", &settings),
+            Err(TagRejection::TooCommon(_))
+        ));
+        assert!(validate("the", "meow", &settings).is_ok());
+    }
+}
@@ -2,6 +2,10 @@ use serenity::{
     builder::CreateApplicationCommands, model::prelude::command::CommandOptionType,
 };
 
+use super::{
+    global_data::{LoopMode, MAX_VOLUME},
+    search::MAX_SEARCH_QUERY_LENGTH,
+};
 use crate::client::slash_commands::UserCommand;
 
 pub trait VoiceCommandBuilder {
@@ -9,11 +13,52 @@ pub trait VoiceCommandBuilder {
 }
 
 impl VoiceCommandBuilder for CreateApplicationCommands {
+    #[allow(clippy::too_many_lines)]
     fn create_voice_commands(&mut self) -> &mut Self {
         self.create_application_command(|command| {
             command
                 .name(UserCommand::play)
                 .description("play song from youtube")
+                .create_option(|option| {
+                    option
+                        .name("query")
+                        .description(
+                            "what to search youtube for, or a link, audio files are only played from discord links",
+                        )
+                        .kind(CommandOptionType::String)
+                        .max_length(MAX_SEARCH_QUERY_LENGTH)
+                        .required(true)
+                })
+        })
+        .create_application_command(|command| {
+            command
+                .name(UserCommand::play_file)
+                .description("play an audio file")
+                .create_option(|option| {
+                    option
+                        .name("file")
+                        .description("The audio file to play")
+                        .kind(CommandOptionType::Attachment)
+                        .required(true)
+                })
+        })
+        .create_application_command(|command| {
+            command
+                .name(UserCommand::play_local)
+                .description("play a song from the bot's music folder")
+                .create_option(|option| {
+                    option
+                        .name("name")
+                        .description("The song to play")
+                        .kind(CommandOptionType::String)
+                        .set_autocomplete(true)
+                        .required(true)
+                })
+        })
+        .create_application_command(|command| {
+            command
+                .name(UserCommand::search)
+                .description("search youtube and pick which song to play")
                 .create_option(|option| {
                     option
                         .name("query")
@@ -35,6 +80,28 @@ impl VoiceCommandBuilder for CreateApplicationCommands {
                         .required(false)
                 })
         })
+        .create_application_command(|command| {
+            command
+                .name(UserCommand::pause)
+                .description("pause the current song")
+        })
+        .create_application_command(|command| {
+            command
+                .name(UserCommand::resume)
+                .description("resume the paused song")
+        })
+        .create_application_command(|command| {
+            command
+                .name(UserCommand::seek)
+                .description("jump to a time in the current song")
+                .create_option(|option| {
+                    option
+                        .name("time")
+                        .description("Like 1:23 or 90s, +30s and -1:00 move from the current position")
+                        .kind(CommandOptionType::String)
+                        .required(true)
+                })
+        })
         //stop playing
         .create_application_command(|command| {
             command
@@ -56,7 +123,17 @@ impl VoiceCommandBuilder for CreateApplicationCommands {
         .create_application_command(|command| {
             command
                 .name(UserCommand::loop_song)
-                .description("loop the current song")
+                .description("loop the current song or the whole queue")
+                .create_option(|option| {
+                    option
+                        .name("mode")
+                        .description("What to loop")
+                        .kind(CommandOptionType::String)
+                        .add_string_choice("Off", LoopMode::Off)
+                        .add_string_choice("Current song", LoopMode::Track)
+                        .add_string_choice("Whole queue", LoopMode::Queue)
+                        .required(true)
+                })
         })
         .create_application_command(|command| {
             command
@@ -77,5 +154,221 @@ impl VoiceCommandBuilder for CreateApplicationCommands {
                         .kind(CommandOptionType::Integer)
                 })
         })
+        .create_application_command(|command| {
+            command
+                .name(UserCommand::shuffle)
+                .description("shuffle the songs after the current one")
+        })
+        .create_application_command(|command| {
+            command
+                .name(UserCommand::move_song)
+                .description("move a song to another position in the queue")
+                .create_option(|option| {
+                    option
+                        .name("from")
+                        .description("The track to move")
+                        .required(true)
+                        .kind(CommandOptionType::Integer)
+                })
+                .create_option(|option| {
+                    option
+                        .name("to")
+                        .description("Where to move it")
+                        .required(true)
+                        .kind(CommandOptionType::Integer)
+                })
+        })
+        .create_application_command(|command| {
+            command
+                .name(UserCommand::remove)
+                .description("remove a song or a range of songs from the queue")
+                .create_option(|option| {
+                    option
+                        .name("from")
+                        .description("The first track to remove")
+                        .required(true)
+                        .kind(CommandOptionType::Integer)
+                })
+                .create_option(|option| {
+                    option
+                        .name("to")
+                        .description("The last track to remove, only the first one if left empty")
+                        .required(false)
+                        .kind(CommandOptionType::Integer)
+                })
+        })
+        .create_application_command(|command| {
+            command
+                .name(UserCommand::remove_by)
+                .description("remove every song a user added to the queue")
+                .create_option(|option| {
+                    option
+                        .name("user")
+                        .description("The user whose songs to remove, your own if left empty")
+                        .required(false)
+                        .kind(CommandOptionType::User)
+                })
+        })
+        .create_application_command(|command| {
+            command
+                .name(UserCommand::clear)
+                .description("remove every song from the queue except the current one")
+        })
+        .create_application_command(|command| {
+            command
+                .name(UserCommand::volume)
+                .description("change the volume of the songs")
+                .create_option(|option| {
+                    option
+                        .name("percent")
+                        .description("The new volume")
+                        .kind(CommandOptionType::Integer)
+                        .min_int_value(0)
+                        .max_int_value(MAX_VOLUME)
+                        .required(true)
+                })
+        })
+        .create_application_command(|command| {
+            command
+                .name(UserCommand::playlist)
+                .description("save songs to your own playlists and play them later")
+                .create_option(|option| {
+                    option
+                        .name("create")
+                        .description("Make a new empty playlist")
+                        .kind(CommandOptionType::SubCommand)
+                        .create_sub_option(|option| {
+                            option
+                                .name("name")
+                                .description("The name of the playlist")
+                                .kind(CommandOptionType::String)
+                                .required(true)
+                        })
+                        .create_sub_option(|option| {
+                            option
+                                .name("shared")
+                                .description("Let everyone in this server play it")
+                                .kind(CommandOptionType::Boolean)
+                                .required(false)
+                        })
+                })
+                .create_option(|option| {
+                    option
+                        .name("add")
+                        .description("Add a song or every song of a playlist link to one of your playlists")
+                        .kind(CommandOptionType::SubCommand)
+                        .create_sub_option(|option| {
+                            option
+                                .name("playlist")
+                                .description("The playlist to add to")
+                                .kind(CommandOptionType::String)
+                                .set_autocomplete(true)
+                                .required(true)
+                        })
+                        .create_sub_option(|option| {
+                            option
+                                .name("query")
+                                .description("what to search youtube for, or a link")
+                                .kind(CommandOptionType::String)
+                                .required(true)
+                        })
+                })
+                .create_option(|option| {
+                    option
+                        .name("remove")
+                        .description("Remove a song from one of your playlists")
+                        .kind(CommandOptionType::SubCommand)
+                        .create_sub_option(|option| {
+                            option
+                                .name("playlist")
+                                .description("The playlist to remove from")
+                                .kind(CommandOptionType::String)
+                                .set_autocomplete(true)
+                                .required(true)
+                        })
+                        .create_sub_option(|option| {
+                            option
+                                .name("position")
+                                .description("The number of the song, shown by /playlist list")
+                                .kind(CommandOptionType::Integer)
+                                .min_int_value(1)
+                                .required(true)
+                        })
+                })
+                .create_option(|option| {
+                    option
+                        .name("list")
+                        .description("Show the playlists you can play, or the songs of one")
+                        .kind(CommandOptionType::SubCommand)
+                        .create_sub_option(|option| {
+                            option
+                                .name("playlist")
+                                .description("The playlist to show, every playlist if left empty")
+                                .kind(CommandOptionType::String)
+                                .set_autocomplete(true)
+                                .required(false)
+                        })
+                })
+                .create_option(|option| {
+                    option
+                        .name("play")
+                        .description("Queue every song of a playlist")
+                        .kind(CommandOptionType::SubCommand)
+                        .create_sub_option(|option| {
+                            option
+                                .name("playlist")
+                                .description("Your playlist or one shared in this server")
+                                .kind(CommandOptionType::String)
+                                .set_autocomplete(true)
+                                .required(true)
+                        })
+                })
+                .create_option(|option| {
+                    option
+                        .name("delete")
+                        .description("Delete one of your playlists")
+                        .kind(CommandOptionType::SubCommand)
+                        .create_sub_option(|option| {
+                            option
+                                .name("playlist")
+                                .description("The playlist to delete")
+                                .kind(CommandOptionType::String)
+                                .set_autocomplete(true)
+                                .required(true)
+                        })
+                })
+                .create_option(|option| {
+                    option
+                        .name("save-queue")
+                        .description("Save the songs in the queue as a playlist, replacing it if you already have one with that name")
+                        .kind(CommandOptionType::SubCommand)
+                        .create_sub_option(|option| {
+                            option
+                                .name("name")
+                                .description("The name of the playlist")
+                                .kind(CommandOptionType::String)
+                                .required(true)
+                        })
+                        .create_sub_option(|option| {
+                            option
+                                .name("shared")
+                                .description("Let everyone in this server play it")
+                                .kind(CommandOptionType::Boolean)
+                                .required(false)
+                        })
+                })
+        })
+        .create_application_command(|command| {
+            command
+                .name(UserCommand::set_dj_role)
+                .description("only let a role control the songs, like skipping and changing the volume")
+                .create_option(|option| {
+                    option
+                        .name("role")
+                        .description("The DJ role, everyone can control the songs if left empty")
+                        .kind(CommandOptionType::Role)
+                        .required(false)
+                })
+        })
     }
 }
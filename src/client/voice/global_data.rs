@@ -0,0 +1,270 @@
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use serenity::{
+    model::id::UserId,
+    prelude::{RwLock, TypeMap, TypeMapKey},
+};
+use songbird::input::{Input, Metadata};
+use std::{error::Error, fs, sync::Arc, time::Duration};
+use strum_macros::{Display, EnumString};
+use tokio::sync::RwLockWriteGuard;
+use uuid::Uuid;
+
+use super::sources::{default_source_resolvers, resolve_saved_source, SourceResolver};
+use crate::client::file_operations::create_file_if_missing;
+
+/// Voice configuration for a single guild
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VoiceSettings {
+    /// Volume new tracks start at, in percent
+    pub volume: u8,
+    /// If set only this role and admins can change the playback
+    pub dj_role: Option<u64>,
+}
+
+impl Default for VoiceSettings {
+    fn default() -> Self {
+        Self {
+            volume: DEFAULT_VOLUME,
+            dj_role: None,
+        }
+    }
+}
+
+/// The volume tracks were always played at before it could be changed
+pub const DEFAULT_VOLUME: u8 = 50;
+pub const MAX_VOLUME: u8 = 200;
+
+/// Guild, [`VoiceSettings`]
+pub struct VoiceSettingsContainer;
+impl TypeMapKey for VoiceSettingsContainer {
+    type Value = Arc<DashMap<u64, VoiceSettings>>;
+}
+pub const VOICE_SETTINGS_PATH: &str = "data/voice settings.json";
+
+/// What happens when a track ends
+#[derive(Clone, Copy, Default, PartialEq, Eq, Display, EnumString, Serialize, Deserialize)]
+pub enum LoopMode {
+    #[default]
+    #[strum(serialize = "off")]
+    Off,
+    /// Every track that starts playing loops until it's skipped
+    #[strum(serialize = "track")]
+    Track,
+    /// Tracks that finish get added to the end of the queue again
+    #[strum(serialize = "queue")]
+    Queue,
+}
+
+/// Guild, [`LoopMode`]. This isn't saved to a file, guilds that aren't looping aren't in it
+pub struct LoopModes;
+impl TypeMapKey for LoopModes {
+    type Value = Arc<DashMap<u64, LoopMode>>;
+}
+
+/// What the bot knows about a queued track that songbird doesn't
+#[derive(Clone)]
+pub struct TrackData {
+    /// The user who added the track, restored tracks saved before requesters were kept don't have one
+    pub requester: Option<UserId>,
+}
+
+/// Track UUID, [`TrackData`]. This isn't saved to a file, tracks are removed from it when they end
+pub struct TrackDataContainer;
+impl TypeMapKey for TrackDataContainer {
+    type Value = Arc<DashMap<Uuid, TrackData>>;
+}
+
+/// A track saved so it can be played again later, in a [`QueueSession`] or a [`SavedPlaylist`]
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SavedTrack {
+    /// What the track is resolved from, see [`SourceResolver`]
+    pub source: String,
+    pub title: Option<String>,
+    pub channel: Option<String>,
+    pub duration: Option<Duration>,
+    /// Only queued tracks have one, playlist songs are requested by whoever plays the playlist
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub requester: Option<u64>,
+}
+
+impl SavedTrack {
+    /// Saves the track if it knows where it was played from
+    pub fn from_metadata(metadata: &Metadata, requester: Option<UserId>) -> Option<Self> {
+        Some(Self {
+            source: metadata.source_url.clone()?,
+            title: metadata.title.clone(),
+            channel: metadata.channel.clone(),
+            duration: metadata.duration,
+            requester: requester.map(|requester| requester.0),
+        })
+    }
+
+    /// A lazy input so saved songs aren't all downloaded at once, the saved metadata is kept
+    /// because lazy inputs don't know it until they play
+    pub async fn into_lazy_input(self, data: &Arc<RwLock<TypeMap>>) -> Option<Input> {
+        let mut input = resolve_saved_source(data, &self.source, true).await.ok()?;
+        input.metadata.title = self.title.or(input.metadata.title.take());
+        input.metadata.channel = self.channel.or(input.metadata.channel.take());
+        input.metadata.duration = self.duration.or(input.metadata.duration);
+
+        Some(input)
+    }
+}
+
+/// The queue of a guild, saved whenever it changes
+#[derive(Clone, Serialize, Deserialize)]
+pub struct QueueSession {
+    pub voice_channel: u64,
+    /// Where now playing messages are sent
+    pub text_channel: u64,
+    /// The first track is the one that was playing
+    pub tracks: Vec<SavedTrack>,
+    /// How far into the first track it was
+    pub position: Duration,
+    pub loop_mode: LoopMode,
+}
+
+/// Guild, [`QueueSession`]. Guilds that aren't playing anything aren't in it
+pub struct QueueSessions;
+impl TypeMapKey for QueueSessions {
+    type Value = Arc<DashMap<u64, QueueSession>>;
+}
+pub const QUEUE_SESSIONS_PATH: &str = "data/queue sessions.json";
+
+/// A playlist a user saved with `/playlist`
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SavedPlaylist {
+    pub name: String,
+    /// If set everyone in this guild can play the playlist, not only its owner
+    pub shared_guild: Option<u64>,
+    pub tracks: Vec<SavedTrack>,
+}
+
+/// Owner, their [`SavedPlaylist`]s
+pub struct SavedPlaylists;
+impl TypeMapKey for SavedPlaylists {
+    type Value = Arc<DashMap<u64, Vec<SavedPlaylist>>>;
+}
+pub const SAVED_PLAYLISTS_PATH: &str = "data/saved playlists.json";
+
+/// The [`SourceResolver`]s in the order they're tried, the first one that can resolve a query plays it
+pub struct SourceResolvers;
+impl TypeMapKey for SourceResolvers {
+    type Value = Arc<Vec<Box<dyn SourceResolver>>>;
+}
+
+pub async fn get_voice_settings_lock(
+    data: &Arc<RwLock<TypeMap>>,
+) -> Arc<DashMap<u64, VoiceSettings>> {
+    let voice_settings_lock = data
+        .read()
+        .await
+        .get::<VoiceSettingsContainer>()
+        .expect("expected VoiceSettingsContainer in TypeMap")
+        .clone();
+    voice_settings_lock
+}
+
+pub async fn get_loop_modes_lock(data: &Arc<RwLock<TypeMap>>) -> Arc<DashMap<u64, LoopMode>> {
+    let loop_modes_lock = data
+        .read()
+        .await
+        .get::<LoopModes>()
+        .expect("expected LoopModes in TypeMap")
+        .clone();
+    loop_modes_lock
+}
+
+pub async fn get_track_data_lock(data: &Arc<RwLock<TypeMap>>) -> Arc<DashMap<Uuid, TrackData>> {
+    let track_data_lock = data
+        .read()
+        .await
+        .get::<TrackDataContainer>()
+        .expect("expected TrackDataContainer in TypeMap")
+        .clone();
+    track_data_lock
+}
+
+pub async fn get_source_resolvers_lock(
+    data: &Arc<RwLock<TypeMap>>,
+) -> Arc<Vec<Box<dyn SourceResolver>>> {
+    let source_resolvers_lock = data
+        .read()
+        .await
+        .get::<SourceResolvers>()
+        .expect("expected SourceResolvers in TypeMap")
+        .clone();
+    source_resolvers_lock
+}
+
+pub async fn get_queue_sessions_lock(
+    data: &Arc<RwLock<TypeMap>>,
+) -> Arc<DashMap<u64, QueueSession>> {
+    let queue_sessions_lock = data
+        .read()
+        .await
+        .get::<QueueSessions>()
+        .expect("expected QueueSessions in TypeMap")
+        .clone();
+    queue_sessions_lock
+}
+
+pub async fn get_saved_playlists_lock(
+    data: &Arc<RwLock<TypeMap>>,
+) -> Arc<DashMap<u64, Vec<SavedPlaylist>>> {
+    let saved_playlists_lock = data
+        .read()
+        .await
+        .get::<SavedPlaylists>()
+        .expect("expected SavedPlaylists in TypeMap")
+        .clone();
+    saved_playlists_lock
+}
+
+pub fn save_saved_playlists(
+    saved_playlists: &DashMap<u64, Vec<SavedPlaylist>>,
+) -> Result<(), std::io::Error> {
+    fs::write(
+        SAVED_PLAYLISTS_PATH,
+        serde_json::to_string(saved_playlists).expect("Serialization failed"),
+    )
+}
+
+pub fn save_queue_sessions(
+    queue_sessions: &DashMap<u64, QueueSession>,
+) -> Result<(), std::io::Error> {
+    fs::write(
+        QUEUE_SESSIONS_PATH,
+        serde_json::to_string(queue_sessions).expect("Serialization failed"),
+    )
+}
+
+pub fn save_voice_settings(
+    voice_settings: &DashMap<u64, VoiceSettings>,
+) -> Result<(), std::io::Error> {
+    fs::write(
+        VOICE_SETTINGS_PATH,
+        serde_json::to_string(voice_settings).expect("Serialization failed"),
+    )
+}
+
+pub fn init_voice_data(data: &mut RwLockWriteGuard<TypeMap>) -> Result<(), Box<dyn Error>> {
+    let voice_settings: DashMap<u64, VoiceSettings> = serde_json::from_str(&fs::read_to_string(
+        create_file_if_missing(VOICE_SETTINGS_PATH, "{}")?,
+    )?)?;
+    data.insert::<VoiceSettingsContainer>(Arc::new(voice_settings));
+    data.insert::<LoopModes>(Arc::new(DashMap::new()));
+    data.insert::<TrackDataContainer>(Arc::new(DashMap::new()));
+    let queue_sessions: DashMap<u64, QueueSession> = serde_json::from_str(&fs::read_to_string(
+        create_file_if_missing(QUEUE_SESSIONS_PATH, "{}")?,
+    )?)?;
+    data.insert::<QueueSessions>(Arc::new(queue_sessions));
+    let saved_playlists: DashMap<u64, Vec<SavedPlaylist>> = serde_json::from_str(
+        &fs::read_to_string(create_file_if_missing(SAVED_PLAYLISTS_PATH, "{}")?)?,
+    )?;
+    data.insert::<SavedPlaylists>(Arc::new(saved_playlists));
+    data.insert::<SourceResolvers>(Arc::new(default_source_resolvers()));
+    Ok(())
+}
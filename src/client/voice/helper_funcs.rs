@@ -1,3 +1,6 @@
+use std::{ops::ControlFlow, time::Duration};
+
+use super::{global_data::get_track_data_lock, sessions::save_session};
 use serenity::{
     client::Context,
     model::{
@@ -7,6 +10,46 @@ use serenity::{
         prelude::{interaction::application_command::ApplicationCommandInteraction, VoiceState},
     },
 };
+use songbird::tracks::TrackHandle;
+
+/// Formats the duration as `m:ss`, or `h:mm:ss` if it's longer than an hour
+pub fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+
+    if hours > 0 {
+        format!("{hours}:{minutes:02}:{seconds:02}")
+    } else {
+        format!("{minutes}:{seconds:02}")
+    }
+}
+
+/// A text bar that shows how far into the track the position is
+pub fn progress_bar(position: Duration, duration: Duration) -> String {
+    const LENGTH: u8 = 20;
+
+    let progress = if duration.is_zero() {
+        0.0
+    } else {
+        (position.as_secs_f64() / duration.as_secs_f64()).min(1.0)
+    };
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    let filled = (progress * f64::from(LENGTH - 1)).round() as usize;
+
+    format!(
+        "{}🔘{}",
+        "▬".repeat(filled),
+        "▬".repeat(usize::from(LENGTH - 1) - filled)
+    )
+}
+
+/// The user who added the track to the queue
+pub async fn get_requester(ctx: &Context, track: &TrackHandle) -> Option<UserId> {
+    get_track_data_lock(&ctx.data)
+        .await
+        .get(&track.uuid())
+        .and_then(|track_data| track_data.requester)
+}
 
 pub fn get_voice_channel_of_user(guild: &Guild, user_id: UserId) -> Option<ChannelId> {
     guild
@@ -44,6 +87,28 @@ pub fn is_bot_in_another_channel(ctx: &Context, guild: &Guild, user_id: UserId)
     false
 }
 
+/// Tells the user to join the bot's voice channel if they're in another one
+pub async fn respond_if_not_same_vc(
+    guild_id: serenity::model::id::GuildId,
+    ctx: &Context,
+    command: &ApplicationCommandInteraction,
+) -> ControlFlow<()> {
+    if let Some(guild) = guild_id.to_guild_cached(&ctx.cache) {
+        if is_bot_in_another_channel(ctx, &guild, command.user.id) {
+            command
+                .create_interaction_response(&ctx.http, |r| {
+                    r.interaction_response_data(|d| {
+                        d.content("Must be in the same voice channel to use that command!")
+                    })
+                })
+                .await
+                .expect("Error creating interaction response");
+            return ControlFlow::Break(());
+        }
+    }
+    ControlFlow::Continue(())
+}
+
 pub async fn get_call_lock(
     ctx: &Context,
     guild_id: serenity::model::id::GuildId,
@@ -115,6 +180,7 @@ pub async fn leave_vc_if_alone(old: Option<VoiceState>, ctx: &Context) {
 
     if changed_voice_channel_members.len() == 1 {
         call.queue().stop();
+        save_session(ctx, old.guild_id.unwrap(), call.queue()).await;
         call.leave().await.expect("Couldn't leave voice channel");
     }
 }
@@ -0,0 +1,267 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+use serenity::{
+    client::Context,
+    model::prelude::interaction::{
+        application_command::{ApplicationCommandInteraction, CommandDataOptionValue},
+        autocomplete::AutocompleteInteraction,
+    },
+};
+
+use super::{
+    play::{enqueue_input, join_command_call, return_response},
+    sources::{
+        has_audio_extension, resolve_with, HttpAudioResolver, LocalFileResolver, SourceResolver,
+    },
+};
+use crate::client::helper_funcs::{get_option, respond_with_content, MAX_AUTOCOMPLETE_CHOICES};
+
+/// Play an uploaded audio file
+pub async fn play_file(ctx: &Context, command: &ApplicationCommandInteraction) {
+    let Some(CommandDataOptionValue::Attachment(attachment)) = get_option(command, "file") else {
+        respond_with_content(ctx, command, "Something went wrong").await;
+        return;
+    };
+
+    let is_audio = attachment.content_type.as_ref().map_or_else(
+        || has_audio_extension(Path::new(&attachment.filename)),
+        |content_type| content_type.starts_with("audio/") || content_type.starts_with("video/"),
+    );
+    if !is_audio {
+        respond_with_content(ctx, command, "That isn't an audio file").await;
+        return;
+    }
+
+    play_source(ctx, command, &HttpAudioResolver, &attachment.url).await;
+}
+
+/// Play a file from the bot owner's `LOCAL_MUSIC_DIR`
+pub async fn play_local(ctx: &Context, command: &ApplicationCommandInteraction) {
+    let Some(music_dir) = get_local_music_dir() else {
+        respond_with_content(
+            ctx,
+            command,
+            "The bot owner hasn't set up a local music folder",
+        )
+        .await;
+        return;
+    };
+
+    let Some(CommandDataOptionValue::String(name)) = get_option(command, "name") else {
+        respond_with_content(ctx, command, "Something went wrong").await;
+        return;
+    };
+
+    if find_local_file(&music_dir, name).is_none() {
+        respond_with_content(ctx, command, format!("There's no song called {name}")).await;
+        return;
+    }
+
+    play_source(ctx, command, &LocalFileResolver { music_dir }, name).await;
+}
+
+/// Suggests the local songs whose names contain what the user has typed so far
+pub async fn autocomplete_local_music(ctx: &Context, autocomplete: &AutocompleteInteraction) {
+    let input = autocomplete
+        .data
+        .options
+        .iter()
+        .find(|option| option.focused)
+        .and_then(|option| option.value.as_ref())
+        .and_then(|value| value.as_str())
+        .unwrap_or_default()
+        .to_lowercase();
+
+    // Reading the folder blocks, so it's kept off the threads that handle events
+    let music = match get_local_music_dir() {
+        Some(music_dir) => tokio::task::spawn_blocking(move || list_local_music(&music_dir))
+            .await
+            .unwrap_or_default(),
+        None => Vec::new(),
+    };
+    let mut names: Vec<String> = music
+        .into_iter()
+        .filter(|name| name.to_lowercase().contains(input.trim()))
+        .collect();
+    names.sort_unstable();
+
+    autocomplete
+        .create_autocomplete_response(&ctx.http, |r| {
+            for name in names.into_iter().take(MAX_AUTOCOMPLETE_CHOICES) {
+                r.add_string_choice(&name, &name);
+            }
+            r
+        })
+        .await
+        .expect("Error creating autocomplete response");
+}
+
+/// Queues the file or url with the resolver that plays it, which is never youtube-dl for audio files
+async fn play_source(
+    ctx: &Context,
+    command: &ApplicationCommandInteraction,
+    resolver: &dyn SourceResolver,
+    source: &str,
+) {
+    let guild_id = command.guild_id.expect("Couldn't get guild ID");
+
+    command.defer(&ctx.http).await.unwrap();
+
+    let Some(call_lock) = join_command_call(ctx, command).await else {
+        return;
+    };
+    let mut call = call_lock.lock().await;
+
+    let input = match resolve_with(resolver, source, false).await {
+        Ok(input) => input,
+        Err(why) => {
+            println!("Err starting source: {why:?}");
+            command
+                .edit_original_interaction_response(&ctx.http, |r| {
+                    r.content("Couldn't play the file")
+                })
+                .await
+                .expect("Error creating interaction response");
+            return;
+        }
+    };
+
+    let metadata = enqueue_input(ctx, &mut call, guild_id, input, Some(command.user.id)).await;
+
+    return_response(&metadata, call.queue(), command, ctx).await;
+}
+
+/// The folder `/play-local` plays from, set with `LOCAL_MUSIC_DIR`
+pub fn get_local_music_dir() -> Option<PathBuf> {
+    env::var("LOCAL_MUSIC_DIR")
+        .ok()
+        .and_then(|music_dir| fs::canonicalize(music_dir).ok())
+}
+
+/// The paths of the audio files in the folder and its subfolders, relative to the folder.
+///
+/// Symlinks are skipped, they could point outside of the folder or back into it
+fn list_local_music(music_dir: &Path) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut folders = vec![music_dir.to_path_buf()];
+
+    while let Some(folder) = folders.pop() {
+        let Ok(entries) = fs::read_dir(&folder) else {
+            continue;
+        };
+
+        for path in entries.flatten().map(|entry| entry.path()) {
+            let Ok(metadata) = fs::symlink_metadata(&path) else {
+                continue;
+            };
+
+            if metadata.is_dir() {
+                folders.push(path);
+            } else if metadata.is_file() && has_audio_extension(&path) {
+                if let Ok(name) = path.strip_prefix(music_dir) {
+                    names.push(name.to_string_lossy().into_owned());
+                }
+            }
+        }
+    }
+
+    names
+}
+
+/// Finds the audio file in the folder, names can't point outside of it
+pub fn find_local_file(music_dir: &Path, name: &str) -> Option<PathBuf> {
+    let path = fs::canonicalize(music_dir.join(name)).ok()?;
+
+    (path.starts_with(music_dir) && path.is_file() && has_audio_extension(&path)).then_some(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{find_local_file, list_local_music};
+    use crate::client::voice::sources::has_audio_extension;
+    use std::{
+        fs,
+        path::{Path, PathBuf},
+    };
+
+    /// Creates a music folder with a song, a song in a subfolder, a text file and a song outside of it
+    fn create_music_dir(name: &str) -> (PathBuf, PathBuf) {
+        let root =
+            std::env::temp_dir().join(format!("markov_bot_local_{name}_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+
+        let music_dir = root.join("music");
+        fs::create_dir_all(music_dir.join("album")).unwrap();
+        fs::write(music_dir.join("song.mp3"), "").unwrap();
+        fs::write(music_dir.join("album/track.ogg"), "").unwrap();
+        fs::write(music_dir.join("notes.txt"), "").unwrap();
+        fs::write(root.join("secret.mp3"), "").unwrap();
+
+        (fs::canonicalize(music_dir).unwrap(), root)
+    }
+
+    #[test]
+    fn audio_extensions_are_recognised() {
+        assert!(has_audio_extension(Path::new("song.mp3")));
+        assert!(has_audio_extension(Path::new("album/Song.FLAC")));
+        assert!(!has_audio_extension(Path::new("notes.txt")));
+        assert!(!has_audio_extension(Path::new("mp3")));
+        assert!(!has_audio_extension(Path::new("")));
+    }
+
+    #[test]
+    fn local_files_are_found() {
+        let (music_dir, root) = create_music_dir("found");
+
+        assert_eq!(
+            find_local_file(&music_dir, "song.mp3"),
+            Some(music_dir.join("song.mp3"))
+        );
+        assert_eq!(
+            find_local_file(&music_dir, "album/track.ogg"),
+            Some(music_dir.join("album/track.ogg"))
+        );
+        assert_eq!(find_local_file(&music_dir, "notes.txt"), None);
+        assert_eq!(find_local_file(&music_dir, "album"), None);
+        assert_eq!(find_local_file(&music_dir, "missing.mp3"), None);
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn files_outside_the_folder_arent_found() {
+        let (music_dir, root) = create_music_dir("traversal");
+
+        assert_eq!(find_local_file(&music_dir, "../secret.mp3"), None);
+        assert_eq!(find_local_file(&music_dir, "album/../../secret.mp3"), None);
+        let absolute = root.join("secret.mp3");
+        assert_eq!(
+            find_local_file(&music_dir, &absolute.to_string_lossy()),
+            None
+        );
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlinks_out_of_the_folder_arent_followed() {
+        let (music_dir, root) = create_music_dir("symlinks");
+        std::os::unix::fs::symlink(root.join("secret.mp3"), music_dir.join("linked.mp3")).unwrap();
+        std::os::unix::fs::symlink(&root, music_dir.join("outside")).unwrap();
+        // Points back at the folder it's in, following it would never end
+        std::os::unix::fs::symlink(&music_dir, music_dir.join("album/loop")).unwrap();
+
+        assert_eq!(find_local_file(&music_dir, "linked.mp3"), None);
+        assert_eq!(find_local_file(&music_dir, "outside/secret.mp3"), None);
+
+        let mut names = list_local_music(&music_dir);
+        names.sort_unstable();
+        assert_eq!(names, ["album/track.ogg", "song.mp3"]);
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
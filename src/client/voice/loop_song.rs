@@ -1,80 +1,73 @@
+use std::{ops::ControlFlow, str::FromStr};
+
 use serenity::{
-    client::Context, model::prelude::interaction::application_command::ApplicationCommandInteraction,
+    client::Context,
+    model::prelude::interaction::application_command::{
+        ApplicationCommandInteraction, CommandDataOptionValue,
+    },
+};
+
+use super::{
+    global_data::{get_loop_modes_lock, LoopMode},
+    helper_funcs::{get_call_lock, respond_if_not_same_vc},
+    permissions::respond_if_not_dj,
+    sessions::save_session,
 };
-use songbird::tracks::LoopState;
+use crate::client::helper_funcs::{get_option, respond_with_content};
 
-/// Loop the current track
+/// Loop the current track, the whole queue or stop looping
 pub async fn loop_song(ctx: &Context, command: &ApplicationCommandInteraction) {
     let guild_id = command.guild_id.expect("Couldn't get guild ID");
 
-    let manager = songbird::get(ctx)
-        .await
-        .expect("Songbird Voice client placed in at initialisation.")
-        .clone();
+    let Some(CommandDataOptionValue::String(mode)) = get_option(command, "mode") else {
+        respond_with_content(ctx, command, "Something went wrong").await;
+        return;
+    };
+    let Ok(mode) = LoopMode::from_str(mode) else {
+        respond_with_content(ctx, command, "Something went wrong").await;
+        return;
+    };
 
-    // Get call
-    let call_lock = manager.get(guild_id.0);
-    if call_lock.is_none() {
-        command
-            .create_interaction_response(&ctx.http, |r| {
-                r.interaction_response_data(|d| {
-                    d.content("Must be in a voice channel to use that command!")
-                })
-            })
-            .await
-            .expect("Error creating interaction response");
+    if let ControlFlow::Break(()) = respond_if_not_dj(ctx, command).await {
         return;
     }
-    let call_lock = call_lock.expect("Couldn't get handler lock");
-    let call = call_lock.lock().await;
 
-    if call.queue().current().is_none() {
-        command
-            .create_interaction_response(&ctx.http, |r| {
-                r.interaction_response_data(|d| d.content("Nothing is playing."))
-            })
-            .await
-            .expect("Couldn't create response");
+    if let ControlFlow::Break(()) = respond_if_not_same_vc(guild_id, ctx, command).await {
         return;
     }
 
-    let track = call.queue().current().unwrap();
-
-    match track.get_info().await.unwrap().loops {
-        LoopState::Finite(loop_state) => {
-            if loop_state == 0 {
-                track.enable_loop().unwrap();
+    let Some(call_lock) = get_call_lock(ctx, guild_id, command).await else {
+        return;
+    };
+    let call = call_lock.lock().await;
 
-                command
-                    .create_interaction_response(&ctx.http, |m| {
-                        m.interaction_response_data(|d| d.content("Looping the current song."))
-                    })
-                    .await
-                    .expect("Error creating interaction response");
-            } else {
-                track.disable_loop().unwrap();
+    if let Some(track) = call.queue().current() {
+        let looped = if mode == LoopMode::Track {
+            track.enable_loop()
+        } else {
+            track.disable_loop()
+        };
 
-                command
-                    .create_interaction_response(&ctx.http, |m| {
-                        m.interaction_response_data(|d| {
-                            d.content("No longer looping the current song.")
-                        })
-                    })
-                    .await
-                    .expect("Error creating interaction response");
-            }
+        if looped.is_err() {
+            respond_with_content(ctx, command, "Couldn't change the loop mode.").await;
+            return;
         }
-        LoopState::Infinite => {
-            track.disable_loop().unwrap();
+    }
 
-            command
-                .create_interaction_response(&ctx.http, |m| {
-                    m.interaction_response_data(|d| {
-                        d.content("No longer looping the current song.")
-                    })
-                })
-                .await
-                .expect("Error creating interaction response");
-        }
+    let loop_modes = get_loop_modes_lock(&ctx.data).await;
+    if mode == LoopMode::Off {
+        loop_modes.remove(&guild_id.0);
+    } else {
+        loop_modes.insert(guild_id.0, mode);
     }
+    drop(loop_modes);
+    save_session(ctx, guild_id, call.queue()).await;
+
+    let response = match mode {
+        LoopMode::Off => "No longer looping.",
+        LoopMode::Track => "Looping the current song.",
+        LoopMode::Queue => "Looping the queue, finished songs will be added to the end of it.",
+    };
+
+    respond_with_content(ctx, command, response).await;
 }
@@ -1,33 +1,60 @@
 pub mod commands;
+pub mod global_data;
 pub mod helper_funcs;
+mod local;
 mod loop_song;
+mod pause;
+mod permissions;
 mod play;
 mod playing;
+mod playlist;
 mod queue;
+mod queue_edit;
+mod saved_playlists;
+mod search;
+mod seek;
+mod sessions;
+mod shuffle;
 mod skip;
+mod sources;
 mod stop;
 mod swap;
+mod volume;
 
 use std::sync::Arc;
 
+pub use local::{autocomplete_local_music, play_file, play_local};
 pub use loop_song::loop_song;
+pub use pause::{pause, resume};
+pub use permissions::set_dj_role;
 pub use play::play;
 pub use playing::playing;
 pub use queue::edit_queue;
 pub use queue::queue;
+pub use queue_edit::{clear_queue, move_song, remove_songs, remove_songs_by};
+pub use saved_playlists::{autocomplete_saved_playlists, playlist};
+pub use search::{cancel_search, pick_search_result, search};
+pub use seek::seek;
+pub use sessions::{discard_queue_button, restore_queue_button, restore_sessions};
 use serenity::async_trait;
 use serenity::client::Context;
-use serenity::model::id::ChannelId;
+use serenity::model::id::{ChannelId, GuildId};
 use serenity::prelude::Mutex;
+pub use shuffle::shuffle;
 pub use skip::skip;
-use songbird::tracks::PlayMode;
+use songbird::tracks::{PlayMode, TrackHandle};
 use songbird::Call;
 use songbird::EventContext;
 use songbird::EventHandler;
 pub use stop::stop;
 pub use swap::swap_songs;
+pub use volume::volume;
 
-use crate::client::voice::play::create_track_embed;
+use self::global_data::{get_loop_modes_lock, get_track_data_lock, LoopMode, TrackData};
+use self::helper_funcs::get_requester;
+use self::sessions::save_session;
+use self::sources::resolve_saved_source;
+use crate::client::voice::play::{create_track_embed, enqueue_input};
 /*
  * voice.rs, LsangnaBoi 2022
  * voice channel functionality
@@ -35,6 +62,7 @@ use crate::client::voice::play::create_track_embed;
 
 struct Handler {
     call_lock: Arc<Mutex<Call>>,
+    guild_id: GuildId,
     voice_text_channel: ChannelId,
     ctx: Context,
 }
@@ -48,27 +76,84 @@ impl EventHandler for Handler {
             None
         };
 
-        let (track_state, _) = match track_event {
+        let (track_state, track_handle) = match track_event {
             Some(e) => *e.get(0).unwrap(),
             None => return None,
         };
 
+        // Tracks that were removed from the queue don't have data anymore and are stopped silently
+        let (_, track_data) = get_track_data_lock(&self.ctx.data)
+            .await
+            .remove(&track_handle.uuid())?;
+
+        let loop_mode = get_loop_modes_lock(&self.ctx.data)
+            .await
+            .get(&self.guild_id.0)
+            .map(|loop_mode| *loop_mode)
+            .unwrap_or_default();
+
+        // Skipped and stopped tracks aren't added again, only ones that finished
+        if track_state.playing == PlayMode::End && loop_mode == LoopMode::Queue {
+            self.requeue_track(track_handle, track_data).await;
+        }
+
         if track_state.playing == PlayMode::Stop || track_state.playing == PlayMode::End {
+            if loop_mode == LoopMode::Track {
+                self.loop_current_track().await;
+            }
             self.send_now_playing_msg().await;
         }
 
+        let call = self.call_lock.lock().await;
+        save_session(&self.ctx, self.guild_id, call.queue()).await;
+
         None
     }
 }
 
 impl Handler {
+    /// Adds the track to the end of the queue again
+    async fn requeue_track(&self, track: &TrackHandle, track_data: TrackData) {
+        let Some(url) = track.metadata().source_url.clone() else {
+            return;
+        };
+
+        let mut input = match resolve_saved_source(&self.ctx.data, &url, false).await {
+            Ok(input) => input,
+            Err(why) => {
+                println!("Err requeueing looped track: {why:?}");
+                return;
+            }
+        };
+        // Files don't have the title they were played with otherwise
+        input.metadata = Box::new(track.metadata().clone());
+
+        let mut call = self.call_lock.lock().await;
+        enqueue_input(
+            &self.ctx,
+            &mut call,
+            self.guild_id,
+            input,
+            track_data.requester,
+        )
+        .await;
+    }
+
+    async fn loop_current_track(&self) {
+        let call = self.call_lock.lock().await;
+        if let Some(track) = call.queue().current() {
+            let _ = track.enable_loop();
+        }
+    }
+
     async fn send_now_playing_msg(&self) {
         let call = self.call_lock.lock().await;
         let playing_track = match call.queue().current() {
             Some(e) => e,
             None => return,
         };
-        let embed = create_track_embed(playing_track.metadata());
+        let requester = get_requester(&self.ctx, &playing_track).await;
+        let embed = create_track_embed(playing_track.metadata(), requester);
         self.voice_text_channel
             .send_message(&self.ctx.http, |m| {
                 m.content("Now playing").set_embed(embed)
@@ -0,0 +1,81 @@
+use std::ops::ControlFlow;
+
+use serenity::{
+    client::Context,
+    model::prelude::interaction::application_command::ApplicationCommandInteraction,
+};
+use songbird::tracks::PlayMode;
+
+use super::{
+    helper_funcs::{get_call_lock, respond_if_not_same_vc},
+    permissions::respond_if_not_dj,
+};
+use crate::client::helper_funcs::respond_with_content;
+
+/// Pause the current track
+pub async fn pause(ctx: &Context, command: &ApplicationCommandInteraction) {
+    let guild_id = command.guild_id.expect("Couldn't get guild ID");
+
+    if let ControlFlow::Break(()) = respond_if_not_dj(ctx, command).await {
+        return;
+    }
+
+    if let ControlFlow::Break(()) = respond_if_not_same_vc(guild_id, ctx, command).await {
+        return;
+    }
+
+    let Some(call_lock) = get_call_lock(ctx, guild_id, command).await else {
+        return;
+    };
+    let call = call_lock.lock().await;
+
+    let Some(track) = call.queue().current() else {
+        respond_with_content(ctx, command, "Nothing is playing.").await;
+        return;
+    };
+
+    let response = match track.get_info().await {
+        Ok(info) if info.playing == PlayMode::Pause => "The song is already paused.",
+        Ok(_) => match call.queue().pause() {
+            Ok(()) => "Paused the song.",
+            Err(_) => "Couldn't pause the song.",
+        },
+        Err(_) => "Couldn't pause the song.",
+    };
+
+    respond_with_content(ctx, command, response).await;
+}
+
+/// Resume the current track
+pub async fn resume(ctx: &Context, command: &ApplicationCommandInteraction) {
+    let guild_id = command.guild_id.expect("Couldn't get guild ID");
+
+    if let ControlFlow::Break(()) = respond_if_not_dj(ctx, command).await {
+        return;
+    }
+
+    if let ControlFlow::Break(()) = respond_if_not_same_vc(guild_id, ctx, command).await {
+        return;
+    }
+
+    let Some(call_lock) = get_call_lock(ctx, guild_id, command).await else {
+        return;
+    };
+    let call = call_lock.lock().await;
+
+    let Some(track) = call.queue().current() else {
+        respond_with_content(ctx, command, "Nothing is playing.").await;
+        return;
+    };
+
+    let response = match track.get_info().await {
+        Ok(info) if info.playing == PlayMode::Play => "The song isn't paused.",
+        Ok(_) => match call.queue().resume() {
+            Ok(()) => "Resumed the song.",
+            Err(_) => "Couldn't resume the song.",
+        },
+        Err(_) => "Couldn't resume the song.",
+    };
+
+    respond_with_content(ctx, command, response).await;
+}
@@ -0,0 +1,100 @@
+use super::global_data::{get_voice_settings_lock, save_voice_settings};
+use crate::client::{
+    audit_log::log_setting_change,
+    helper_funcs::{
+        get_option, guild_id_if_admin, is_admin_or_owner, member_has_role, respond_with_content,
+    },
+};
+use std::ops::ControlFlow;
+
+use serenity::{
+    client::Context,
+    model::{
+        id::GuildId,
+        prelude::interaction::{
+            application_command::{ApplicationCommandInteraction, CommandDataOptionValue},
+            message_component::MessageComponentInteraction,
+        },
+        Permissions,
+    },
+};
+
+/// Checks if the user who invoked the command can change the playback of the guild.
+///
+/// If the guild doesn't have a DJ role everyone can, otherwise only the DJs and administrators
+pub async fn can_control_playback(ctx: &Context, command: &ApplicationCommandInteraction) -> bool {
+    let Some(guild_id) = command.guild_id else {
+        return true;
+    };
+
+    let Some(dj_role) = get_dj_role(ctx, guild_id).await else {
+        return true;
+    };
+
+    member_has_role(command, dj_role) || is_admin_or_owner(ctx, command).await
+}
+
+/// Checks if the member who pressed the button can change the playback of the guild,
+/// the same as [`can_control_playback`] except the bot owner isn't let through
+pub async fn can_use_playback_button(ctx: &Context, button: &MessageComponentInteraction) -> bool {
+    let Some(guild_id) = button.guild_id else {
+        return true;
+    };
+
+    let Some(dj_role) = get_dj_role(ctx, guild_id).await else {
+        return true;
+    };
+
+    button.member.as_ref().is_some_and(|member| {
+        member.roles.iter().any(|role| role.0 == dj_role)
+            || member.permissions.is_some_and(Permissions::administrator)
+    })
+}
+
+async fn get_dj_role(ctx: &Context, guild_id: GuildId) -> Option<u64> {
+    get_voice_settings_lock(&ctx.data)
+        .await
+        .get(&guild_id.0)
+        .and_then(|settings| settings.dj_role)
+}
+
+/// Tells the user they need the DJ role if they can't change the playback
+pub async fn respond_if_not_dj(
+    ctx: &Context,
+    command: &ApplicationCommandInteraction,
+) -> ControlFlow<()> {
+    if can_control_playback(ctx, command).await {
+        return ControlFlow::Continue(());
+    }
+
+    respond_with_content(ctx, command, "Only DJs can use that command!").await;
+    ControlFlow::Break(())
+}
+
+/// Sets the role that can change the playback, removes it if no role is given
+pub async fn set_dj_role(ctx: &Context, command: &ApplicationCommandInteraction) {
+    let Some(guild_id) = guild_id_if_admin(ctx, command).await else {
+        return;
+    };
+
+    let role = match get_option(command, "role") {
+        Some(CommandDataOptionValue::Role(role)) => Some(role.id),
+        _ => None,
+    };
+
+    let voice_settings = get_voice_settings_lock(&ctx.data).await;
+    voice_settings.entry(guild_id.0).or_default().dj_role = role.map(|role| role.0);
+
+    let saved = save_voice_settings(&voice_settings).is_ok();
+    let response = match (saved, role) {
+        (true, Some(role)) => format!("Only <@&{role}> and admins can change the playback now"),
+        (true, None) => "Everyone can change the playback now".to_owned(),
+        (false, _) => "Something went wrong while setting the DJ role".to_owned(),
+    };
+
+    respond_with_content(ctx, command, &response).await;
+
+    if saved {
+        log_setting_change(ctx, command, response).await;
+    }
+}
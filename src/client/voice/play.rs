@@ -1,41 +1,83 @@
 use super::{
+    global_data::{get_track_data_lock, TrackData},
     helper_funcs::{get_voice_channel_of_user, is_bot_in_another_channel},
+    playlist::{enqueue_playlist, is_playlist_url},
+    sessions::{save_session, start_session, SessionSaver, SESSION_SAVE_INTERVAL},
+    sources::{resolve_source, ResolveError},
+    volume::get_guild_volume,
     Handler,
 };
 use serenity::{
     builder::CreateEmbed,
     client::Context,
+    model::id::{ChannelId, GuildId, UserId},
+    prelude::{Mentionable, Mutex},
     utils::Colour, model::prelude::interaction::application_command::{ApplicationCommandInteraction, CommandDataOptionValue},
 };
 use songbird::{
     create_player,
-    input::{Input, Metadata, Restartable},
+    input::{Input, Metadata},
     tracks::TrackQueue,
-    TrackEvent,
+    Call, TrackEvent,
 };
-use std::time::Duration;
+use std::{sync::Arc, time::Duration};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum JoinCallError {
+    #[error("You must be in a voice channel to use this command!")]
+    NotInVoiceChannel,
+    #[error("You must be in the same voice channel to use this command!")]
+    InAnotherChannel,
+}
 
 ///play song from youtube
 pub async fn play(ctx: &Context, command: &ApplicationCommandInteraction) {
-    //get the guild ID, cache, and query
+    //get the guild ID and query
     let guild_id = command.guild_id.expect("Couldn't get guild ID");
-    let cache = &ctx.cache;
     let query = get_query(command);
 
     command.defer(&ctx.http).await.unwrap();
 
-    let guild = cache
+    let Some(call_lock) = join_command_call(ctx, command).await else {
+        return;
+    };
+    if is_playlist_url(query) {
+        enqueue_playlist(ctx, command, &call_lock, query).await;
+        return;
+    }
+
+    let mut call = call_lock.lock().await;
+
+    //get the source from youtube, a file or an audio link
+    let Ok(source) = get_source(query, command, ctx).await else {
+        return;
+    };
+
+    //add to queue
+    let metadata = enqueue_input(ctx, &mut call, guild_id, source, Some(command.user.id)).await;
+
+    // Return interaction response
+    return_response(&metadata, call.queue(), command, ctx).await;
+}
+
+/// Joins the voice channel of the user, and if nothing is queued
+/// starts sending now playing messages to the text channel the song was requested in
+pub async fn join_call(
+    ctx: &Context,
+    guild_id: GuildId,
+    user_id: UserId,
+    text_channel: ChannelId,
+) -> Result<Arc<Mutex<Call>>, JoinCallError> {
+    let guild = ctx
+        .cache
         .guild(guild_id)
         .expect("unable to fetch guild from the cache");
 
     // Get voice channel_id
-    let voice_channel_id =
-        if let Some(voice_channel_id) = get_voice_channel_of_user(&guild, command.user.id) {
-            voice_channel_id
-        } else {
-            voice_channel_not_found_response(command, ctx).await;
-            return;
-        };
+    let Some(voice_channel_id) = get_voice_channel_of_user(&guild, user_id) else {
+        return Err(JoinCallError::NotInVoiceChannel);
+    };
 
     //create manager
     let manager = songbird::get(ctx).await.expect("songbird error").clone();
@@ -45,48 +87,110 @@ pub async fn play(ctx: &Context, command: &ApplicationCommandInteraction) {
         None => None,
     };
 
-    if is_bot_in_another_channel(ctx, &guild, command.user.id)
+    if is_bot_in_another_channel(ctx, &guild, user_id)
         && queue.is_some()
         && !queue.expect("Should never fail").is_empty()
     {
-        voice_channel_not_same_response(command, ctx).await;
-        return;
+        return Err(JoinCallError::InAnotherChannel);
     }
 
     //join voice channel
     let (call_lock, success) = manager.join(guild_id, voice_channel_id).await;
     if success.is_err() {
-        voice_channel_not_found_response(command, ctx).await;
-        return;
+        return Err(JoinCallError::NotInVoiceChannel);
     }
+
     let mut call = call_lock.lock().await;
+    if call.queue().is_empty() {
+        add_queue_events(ctx, &call_lock, &mut call, guild_id, text_channel);
+        start_session(ctx, guild_id, voice_channel_id, text_channel).await;
+    }
+    drop(call);
 
-    //get source from YouTube
-    let source = get_source(query.clone(), command, ctx)
-        .await
-        .expect("Couldn't get source");
+    Ok(call_lock)
+}
 
-    if call.queue().is_empty() {
-        call.remove_all_global_events();
-        call.add_global_event(
-            songbird::Event::Track(TrackEvent::End),
-            Handler {
-                call_lock: call_lock.clone(),
-                voice_text_channel: command.channel_id,
-                ctx: ctx.clone(),
-            },
-        );
+/// Replaces the events of the call with the ones that send now playing messages to the text channel,
+/// loop the queue and save it
+pub fn add_queue_events(
+    ctx: &Context,
+    call_lock: &Arc<Mutex<Call>>,
+    call: &mut Call,
+    guild_id: GuildId,
+    text_channel: ChannelId,
+) {
+    call.remove_all_global_events();
+    call.add_global_event(
+        songbird::Event::Track(TrackEvent::End),
+        Handler {
+            call_lock: call_lock.clone(),
+            guild_id,
+            voice_text_channel: text_channel,
+            ctx: ctx.clone(),
+        },
+    );
+    call.add_global_event(
+        songbird::Event::Periodic(SESSION_SAVE_INTERVAL, None),
+        SessionSaver {
+            call_lock: call_lock.clone(),
+            guild_id,
+            ctx: ctx.clone(),
+        },
+    );
+}
+
+/// Joins the voice channel of the user who invoked the deferred command,
+/// or responds with the reason it couldn't
+pub async fn join_command_call(
+    ctx: &Context,
+    command: &ApplicationCommandInteraction,
+) -> Option<Arc<Mutex<Call>>> {
+    let guild_id = command.guild_id.expect("Couldn't get guild ID");
+
+    match join_call(ctx, guild_id, command.user.id, command.channel_id).await {
+        Ok(call_lock) => Some(call_lock),
+        Err(why) => {
+            command
+                .edit_original_interaction_response(&ctx.http, |r| r.content(why))
+                .await
+                .expect("Error creating interaction response");
+            None
+        }
     }
+}
 
-    //add to queue
-    let input: Input = source.into();
-    let metadata = input.metadata.clone();
+/// Adds the input to the end of the queue at the guild's volume and remembers who requested it, if anyone did
+pub async fn enqueue_input(
+    ctx: &Context,
+    call: &mut Call,
+    guild_id: GuildId,
+    input: Input,
+    requester: Option<UserId>,
+) -> Metadata {
+    let metadata = enqueue_input_unsaved(ctx, call, guild_id, input, requester).await;
+    save_session(ctx, guild_id, call.queue()).await;
+
+    metadata
+}
+
+/// Same as `enqueue_input` without saving the queue's session,
+/// for queueing many songs at once and saving after the last one
+pub async fn enqueue_input_unsaved(
+    ctx: &Context,
+    call: &mut Call,
+    guild_id: GuildId,
+    input: Input,
+    requester: Option<UserId>,
+) -> Metadata {
+    let metadata = *input.metadata.clone();
     let (mut audio, _) = create_player(input);
-    audio.set_volume(0.5);
+    audio.set_volume(get_guild_volume(ctx, guild_id).await);
+    get_track_data_lock(&ctx.data)
+        .await
+        .insert(audio.uuid(), TrackData { requester });
     call.enqueue(audio);
 
-    // Return interaction response
-    return_response(&metadata, call.queue(), command, ctx).await;
+    metadata
 }
 
 fn get_query(command: &ApplicationCommandInteraction) -> &String {
@@ -103,77 +207,54 @@ fn get_query(command: &ApplicationCommandInteraction) -> &String {
     }
 }
 
-async fn voice_channel_not_found_response(command: &ApplicationCommandInteraction, ctx: &Context) {
-    command
-        .edit_original_interaction_response(&ctx.http, |r| {
-            r.content("You must be in a voice channel to use this command!")
-        })
-        .await
-        .expect("Error creating interaction response");
-}
-
-async fn voice_channel_not_same_response(command: &ApplicationCommandInteraction, ctx: &Context) {
-    command
-        .edit_original_interaction_response(&ctx.http, |r| {
-            r.content("You must be in the same voice channel to use this command!")
-        })
-        .await
-        .expect("Error creating interaction response");
-}
-
+/// Resolves the query with the resolver for its kind of link, or searches youtube if it isn't a link
 async fn get_source(
-    query: String,
+    query: &str,
     command: &ApplicationCommandInteraction,
     ctx: &Context,
-) -> Result<Restartable, Box<dyn std::error::Error>> {
-    let link_regex =
-    regex::Regex::new(r#"(?:(?:https?|ftp)://|\b(?:[a-z\d]+\.))(?:(?:[^\s()<>]+|\((?:[^\s()<>]+|(?:\([^\s()<>]+\)))?\))+(?:\((?:[^\s()<>]+|(?:\(?:[^\s()<>]+\)))?\)|[^\s`!()\[\]{};:'".,<>?«»“”‘’]))?"#)
-    .expect("Invalid regular expression");
-
-    if link_regex.is_match(&query) {
-        match Restartable::ytdl(query, false).await {
-            Ok(source) => return Ok(source),
-            Err(why) => {
-                println!("Err starting source: {:?}", why);
-                command
-                    .edit_original_interaction_response(&ctx.http, |r| {
-                        r.content("Coulnd't find the video on Youtube")
-                    })
-                    .await
-                    .expect("Error creating interaction response");
-                return Err(Box::new(why));
-            }
-        }
-    }
-
-    match Restartable::ytdl_search(query, false).await {
-        Ok(source) => Ok(source),
+) -> Result<Input, ResolveError> {
+    match resolve_source(&ctx.data, query, false).await {
+        Ok(input) => Ok(input),
         Err(why) => {
-            println!("Err starting source: {:?}", why);
+            println!("Err starting source: {why:?}");
             command
                 .edit_original_interaction_response(&ctx.http, |r| {
-                    r.content("Coulnd't find the video on Youtube")
+                    r.content("Couldn't find the song")
                 })
                 .await
                 .expect("Error creating interaction response");
-            Err(Box::new(why))
+            Err(why)
         }
     }
 }
 
-async fn return_response(
+pub async fn return_response(
     metadata: &Metadata,
     queue: &TrackQueue,
     command: &ApplicationCommandInteraction,
     ctx: &Context,
 ) {
-    let mut embed = create_track_embed(metadata);
+    let (content, embed) = create_queued_response(metadata, queue, command.user.id);
+
+    command
+        .edit_original_interaction_response(&ctx.http, |r| r.add_embed(embed).content(content))
+        .await
+        .expect("Error creating interaction response");
+}
+
+/// The position of the track that was just queued and an embed with its information
+pub fn create_queued_response(
+    metadata: &Metadata,
+    queue: &TrackQueue,
+    requester: UserId,
+) -> (String, CreateEmbed) {
+    let mut embed = create_track_embed(metadata, Some(requester));
 
     let time = metadata.duration.unwrap_or_else(|| Duration::new(0, 0));
     let time_before_song = queue
         .current_queue()
         .iter()
-        .map(|f| f.metadata().duration.unwrap())
+        .map(|f| f.metadata().duration.unwrap_or_default())
         .reduce(|a, f| a.checked_add(f).unwrap())
         .unwrap_or_default()
         - time;
@@ -190,13 +271,10 @@ async fn return_response(
         format!("Position in queue: {}", queue.len())
     };
 
-    command
-        .edit_original_interaction_response(&ctx.http, |r| r.add_embed(embed).content(content))
-        .await
-        .expect("Error creating interaction response");
+    (content, embed)
 }
 
-pub fn create_track_embed(metadata: &Metadata) -> CreateEmbed {
+pub fn create_track_embed(metadata: &Metadata, requester: Option<UserId>) -> CreateEmbed {
     let title = metadata.title.clone().unwrap_or_default();
     let channel = metadata.channel.clone().unwrap_or_default();
     let thumbnail = metadata.thumbnail.clone().unwrap_or_default();
@@ -207,7 +285,7 @@ pub fn create_track_embed(metadata: &Metadata) -> CreateEmbed {
     let duration = format!("{}:{:02}", minutes, seconds);
     let colour = Colour::from_rgb(149, 8, 2);
 
-    let embed = CreateEmbed::default()
+    let mut embed = CreateEmbed::default()
         .title(title)
         .colour(colour)
         .description(channel)
@@ -216,5 +294,9 @@ pub fn create_track_embed(metadata: &Metadata) -> CreateEmbed {
         .url(url)
         .clone();
 
+    if let Some(requester) = requester {
+        embed.field("Requested by: ", requester.mention(), true);
+    }
+
     embed
 }
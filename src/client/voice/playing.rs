@@ -1,5 +1,8 @@
+use super::helper_funcs::{format_duration, get_requester, progress_bar};
+use songbird::tracks::PlayMode;
 use serenity::{
     client::Context,
+    prelude::Mentionable,
     utils::Colour, model::prelude::interaction::application_command::ApplicationCommandInteraction,
 };
 
@@ -28,21 +31,32 @@ pub async fn playing(ctx: &Context, command: &ApplicationCommandInteraction) {
                 return;
             }
 
-            let song = &queue.current().unwrap().metadata().clone();
+            let track = queue.current().unwrap();
+            let song = &track.metadata().clone();
+            let info = track.get_info().await.ok();
+            let status = match &info {
+                Some(info) if info.playing == PlayMode::Pause => "Paused",
+                _ => "Playing",
+            };
+            let position = info.map(|info| info.position).unwrap_or_default();
             //create embed
             //title
-            let title = &song.title.as_ref().unwrap();
+            let title = song.title.clone().unwrap_or_default();
             //channel
-            let channel = &song.channel.as_ref().unwrap();
+            let channel = song.channel.clone().unwrap_or_default();
             //image
-            let thumbnail = &song.thumbnail.as_ref().unwrap();
+            let thumbnail = song.thumbnail.clone().unwrap_or_default();
             //embed
-            let url = &song.source_url.as_ref().unwrap();
+            let url = song.source_url.clone().unwrap_or_default();
             //duration
-            let time = &song.duration.as_ref().unwrap();
-            let minutes = time.as_secs() / 60;
-            let seconds = time.as_secs() - minutes * 60;
-            let duration = format!("{}:{:02}", minutes, seconds);
+            let requester = get_requester(ctx, &track).await;
+            let time = song.duration.unwrap_or_default();
+            let progress = format!(
+                "{} / {}\n{}",
+                format_duration(position),
+                format_duration(time),
+                progress_bar(position, time)
+            );
             //color
             let colour = Colour::from_rgb(149, 8, 2);
             command
@@ -52,9 +66,14 @@ pub async fn playing(ctx: &Context, command: &ApplicationCommandInteraction) {
                             e.title(title)
                                 .colour(colour)
                                 .description(channel)
-                                .field("duration: ", duration, false)
+                                .field("position: ", progress, false)
+                                .field("status: ", status, false)
                                 .thumbnail(thumbnail)
-                                .url(url)
+                                .url(url);
+                            if let Some(requester) = requester {
+                                e.field("requested by: ", requester.mention(), false);
+                            }
+                            e
                         })
                     })
                 })
@@ -0,0 +1,212 @@
+use std::{env, sync::Arc, time::Duration};
+
+use serde_json::Value;
+use serenity::{
+    client::Context,
+    model::prelude::interaction::application_command::ApplicationCommandInteraction,
+    prelude::{Mutex, RwLock, TypeMap},
+    utils::Colour,
+};
+use songbird::{input::Input, Call};
+use thiserror::Error;
+use tokio::process::Command;
+
+use super::{
+    global_data::get_source_resolvers_lock, helper_funcs::format_duration,
+    play::enqueue_input_unsaved, sessions::save_session, sources::resolve_source,
+};
+
+/// How many tracks of a playlist are queued if `MAX_PLAYLIST_TRACKS` isn't set
+const DEFAULT_MAX_PLAYLIST_TRACKS: usize = 50;
+
+#[derive(Debug, Error)]
+pub enum PlaylistError {
+    #[error("Couldn't run youtube-dl: {0}")]
+    CouldntRun(#[from] std::io::Error),
+    #[error("youtube-dl couldn't read the playlist")]
+    InvalidPlaylist,
+    #[error("The playlist is empty")]
+    Empty,
+    #[error("Nothing can read the playlist")]
+    NoResolver,
+}
+
+/// A track of a playlist that hasn't been downloaded yet
+pub struct PlaylistEntry {
+    pub url: String,
+    pub title: Option<String>,
+    pub channel: Option<String>,
+    pub duration: Option<Duration>,
+}
+
+impl PlaylistEntry {
+    /// Only starts youtube-dl when the track is about to play, the metadata comes from the playlist
+    async fn into_lazy_input(self, data: &Arc<RwLock<TypeMap>>) -> Option<Input> {
+        let mut input = resolve_source(data, &self.url, true).await.ok()?;
+
+        input.metadata.source_url = Some(self.url);
+        input.metadata.title = self.title;
+        input.metadata.channel = self.channel;
+        input.metadata.duration = self.duration;
+
+        Some(input)
+    }
+}
+
+/// Checks if the link is a playlist instead of a single video
+pub fn is_playlist_url(query: &str) -> bool {
+    let playlist_regex =
+        regex::Regex::new(r"^https?://\S+[?&]list=[\w-]+").expect("Invalid regular expression");
+
+    playlist_regex.is_match(query.trim())
+}
+
+/// The most tracks a playlist can add to the queue, set with `MAX_PLAYLIST_TRACKS`
+pub fn max_playlist_tracks() -> usize {
+    env::var("MAX_PLAYLIST_TRACKS")
+        .ok()
+        .and_then(|max| max.parse().ok())
+        .filter(|max| *max > 0)
+        .unwrap_or(DEFAULT_MAX_PLAYLIST_TRACKS)
+}
+
+/// Lists the tracks of the playlist without downloading them, with the first resolver that can.
+///
+/// Returns at most `max` tracks, and whether the playlist had more than that
+pub async fn get_playlist_entries(
+    data: &Arc<RwLock<TypeMap>>,
+    url: &str,
+    max: usize,
+) -> Result<(Vec<PlaylistEntry>, bool), PlaylistError> {
+    let resolvers = get_source_resolvers_lock(data).await;
+    for resolver in resolvers
+        .iter()
+        .filter(|resolver| resolver.can_resolve(url))
+    {
+        if let Some(entries) = resolver.list_entries(url, max).await {
+            return entries;
+        }
+    }
+
+    Err(PlaylistError::NoResolver)
+}
+
+/// Lists the tracks of the playlist with youtube-dl
+pub async fn list_ytdl_entries(
+    ytdl_path: &str,
+    url: &str,
+    max: usize,
+) -> Result<(Vec<PlaylistEntry>, bool), PlaylistError> {
+    // One more than the max is asked for to know if the playlist was cut off
+    let output = Command::new(ytdl_path)
+        .args(["--flat-playlist", "-j", "--playlist-end"])
+        .arg((max + 1).to_string())
+        .arg(url)
+        .output()
+        .await?;
+
+    if !output.status.success() {
+        return Err(PlaylistError::InvalidPlaylist);
+    }
+
+    let mut entries: Vec<PlaylistEntry> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| serde_json::from_str::<Value>(line).ok())
+        .filter_map(|entry| parse_playlist_entry(&entry))
+        .collect();
+
+    if entries.is_empty() {
+        return Err(PlaylistError::Empty);
+    }
+
+    let cut_off = entries.len() > max;
+    entries.truncate(max);
+
+    Ok((entries, cut_off))
+}
+
+fn parse_playlist_entry(entry: &Value) -> Option<PlaylistEntry> {
+    let url = entry["url"].as_str()?;
+    // Youtube playlists only have the video id as the url
+    let url = if url.contains("://") {
+        url.to_owned()
+    } else {
+        format!("https://www.youtube.com/watch?v={url}")
+    };
+
+    Some(PlaylistEntry {
+        url,
+        title: entry["title"].as_str().map(ToOwned::to_owned),
+        channel: entry["uploader"]
+            .as_str()
+            .or_else(|| entry["channel"].as_str())
+            .map(ToOwned::to_owned),
+        duration: entry["duration"].as_f64().map(Duration::from_secs_f64),
+    })
+}
+
+/// Queues every track of the playlist and responds with how many were added.
+///
+/// The playlist is read before the call is locked, so the bot can still be used while youtube-dl runs
+pub async fn enqueue_playlist(
+    ctx: &Context,
+    command: &ApplicationCommandInteraction,
+    call_lock: &Arc<Mutex<Call>>,
+    url: &str,
+) {
+    let guild_id = command.guild_id.expect("Couldn't get guild ID");
+    let max = max_playlist_tracks();
+
+    let (entries, cut_off) = match get_playlist_entries(&ctx.data, url, max).await {
+        Ok(entries) => entries,
+        Err(why) => {
+            println!("Err getting playlist: {why:?}");
+            command
+                .edit_original_interaction_response(&ctx.http, |r| {
+                    r.content(format!("Couldn't queue the playlist, {why}"))
+                })
+                .await
+                .expect("Error creating interaction response");
+            return;
+        }
+    };
+
+    let mut inputs = Vec::new();
+    let mut total_duration = Duration::ZERO;
+    for entry in entries {
+        let duration = entry.duration.unwrap_or_default();
+        let Some(input) = entry.into_lazy_input(&ctx.data).await else {
+            continue;
+        };
+
+        inputs.push(input);
+        total_duration += duration;
+    }
+
+    let queued = inputs.len();
+    let mut call = call_lock.lock().await;
+    for input in inputs {
+        enqueue_input_unsaved(ctx, &mut call, guild_id, input, Some(command.user.id)).await;
+    }
+    save_session(ctx, guild_id, call.queue()).await;
+
+    let colour = Colour::from_rgb(149, 8, 2);
+    let description = if cut_off {
+        format!("Only the first {max} songs of the playlist were queued")
+    } else {
+        "Every song of the playlist was queued".to_owned()
+    };
+
+    command
+        .edit_original_interaction_response(&ctx.http, |r| {
+            r.embed(|e| {
+                e.title(format!("Queued {queued} songs"))
+                    .description(description)
+                    .colour(colour)
+                    .field("Length: ", format_duration(total_duration), true)
+                    .field("Songs in queue: ", call.queue().len(), true)
+            })
+        })
+        .await
+        .expect("Error creating interaction response");
+}
@@ -3,7 +3,16 @@ use serenity::{
     utils::Colour, model::prelude::{interaction::{application_command::ApplicationCommandInteraction, message_component::MessageComponentInteraction}, component::ButtonStyle},
 };
 
+use super::{
+    global_data::{get_track_data_lock, TrackData},
+    helper_funcs::format_duration,
+};
 use crate::client::ButtonIds;
+use dashmap::DashMap;
+use serenity::builder::CreateEmbed;
+use songbird::tracks::TrackHandle;
+use std::fmt::Display;
+use uuid::Uuid;
 
 ///get the queue
 pub async fn queue(ctx: &Context, command: &ApplicationCommandInteraction) {
@@ -18,6 +27,7 @@ pub async fn queue(ctx: &Context, command: &ApplicationCommandInteraction) {
         if let Some(handler_lock) = manager.get(guild_id.unwrap()) {
             let handler = handler_lock.lock().await;
             let queue = handler.queue();
+            let track_data = get_track_data_lock(&ctx.data).await;
 
             if queue.is_empty() {
                 command
@@ -38,7 +48,7 @@ pub async fn queue(ctx: &Context, command: &ApplicationCommandInteraction) {
                     let total_queue_time = queue
                         .current_queue()
                         .iter()
-                        .map(|f| f.metadata().duration.unwrap())
+                        .map(|f| f.metadata().duration.unwrap_or_default())
                         .reduce(|a, f| a.checked_add(f).unwrap())
                         .unwrap_or_default();
 
@@ -58,17 +68,8 @@ pub async fn queue(ctx: &Context, command: &ApplicationCommandInteraction) {
                                     ))
                                     .color(colour);
                                 for i in 0..i {
-                                    let song =
-                                        &queue.current_queue().get(i).unwrap().metadata().clone();
-                                    let channel = &song.channel.as_ref().unwrap();
-                                    let title = &song.title.as_ref().unwrap();
-                                    //duration
-                                    let time = &song.duration.as_ref().unwrap();
-                                    let minutes = time.as_secs() / 60;
-                                    let seconds = time.as_secs() - minutes * 60;
-                                    let duration = format!("{}:{:02}", minutes, seconds);
-                                    let arg1 = format!("{}. {} | {}", i + 1, title, channel);
-                                    e.field(arg1, duration, false);
+                                    let track = queue.current_queue().get(i).unwrap().clone();
+                                    add_track_field(e, &track_data, &track, i + 1);
                                 }
                                 e
                             })
@@ -134,6 +135,7 @@ pub async fn edit_queue(
         if let Some(handler_lock) = manager.get(guild_id.unwrap()) {
             let handler = handler_lock.lock().await;
             let queue = handler.queue();
+            let track_data = get_track_data_lock(&ctx.data).await;
 
             button.defer(&ctx.http).await.unwrap();
 
@@ -185,7 +187,7 @@ pub async fn edit_queue(
                     let total_queue_time = queue
                         .current_queue()
                         .iter()
-                        .map(|f| f.metadata().duration.unwrap())
+                        .map(|f| f.metadata().duration.unwrap_or_default())
                         .reduce(|a, f| a.checked_add(f).unwrap())
                         .unwrap_or_default();
 
@@ -205,22 +207,13 @@ pub async fn edit_queue(
                                 .color(colour);
 
                             for i in queue_start..i {
-                                let song = &queue
-                                    .current_queue()
-                                    .get(i as usize)
-                                    .unwrap()
-                                    .metadata()
-                                    .clone();
-
-                                let channel = &song.channel.as_ref().unwrap();
-                                let title = &song.title.as_ref().unwrap();
-                                //duration
-                                let time = &song.duration.as_ref().unwrap();
-                                let minutes = time.as_secs() / 60;
-                                let seconds = time.as_secs() - minutes * 60;
-                                let duration = format!("{}:{:02}", minutes, seconds);
-                                let arg1 = format!("{}. {} | {}", i + 1, title, channel);
-                                e.field(arg1, duration, false);
+                                let Some(track) = usize::try_from(i)
+                                    .ok()
+                                    .and_then(|i| queue.current_queue().get(i).cloned())
+                                else {
+                                    continue;
+                                };
+                                add_track_field(e, &track_data, &track, i + 1);
                             }
                             e
                         })
@@ -259,3 +252,26 @@ pub async fn edit_queue(
         }
     }
 }
+
+/// Adds the position, title, channel, duration and requester of the track to the queue embed
+fn add_track_field(
+    e: &mut CreateEmbed,
+    track_data: &DashMap<Uuid, TrackData>,
+    track: &TrackHandle,
+    position: impl Display,
+) {
+    let song = track.metadata();
+    let title = song.title.clone().unwrap_or_default();
+    let channel = song.channel.clone().unwrap_or_default();
+    let duration = format_duration(song.duration.unwrap_or_default());
+
+    let requester = track_data
+        .get(&track.uuid())
+        .and_then(|data| data.requester);
+    let value = match requester {
+        Some(requester) => format!("{duration} | requested by <@{requester}>"),
+        None => duration,
+    };
+
+    e.field(format!("{position}. {title} | {channel}"), value, false);
+}
@@ -0,0 +1,274 @@
+use std::ops::ControlFlow;
+
+use serenity::{
+    client::Context,
+    model::prelude::interaction::application_command::{
+        ApplicationCommandInteraction, CommandDataOptionValue,
+    },
+};
+use songbird::tracks::{Queued, TrackQueue};
+use thiserror::Error;
+
+use super::{
+    global_data::get_track_data_lock,
+    helper_funcs::{get_call_lock, respond_if_not_same_vc},
+    permissions::respond_if_not_dj,
+    sessions::save_session,
+};
+use crate::client::helper_funcs::{get_option, respond_with_content};
+
+/// Queue positions start at 1, which is the song that's playing
+pub trait QueueEditable {
+    fn move_track(&self, from: usize, to: usize) -> Result<(), QueueEditError>;
+    fn remove_range(&self, from: usize, to: usize) -> Result<Vec<Queued>, QueueEditError>;
+    /// Removes the songs after the current one that match the filter
+    fn remove_upcoming(
+        &self,
+        filter: impl Fn(&Queued) -> bool,
+    ) -> Result<Vec<Queued>, QueueEditError>;
+}
+
+#[derive(Debug, Error)]
+pub enum QueueEditError {
+    #[error("That track isn't in the queue!")]
+    IndexOutOfBounds,
+    #[error("Nothing is playing!")]
+    NothingIsPlaying,
+    #[error("Can't move or remove the song that's currently playing, skip it instead!")]
+    CannotEditCurrentSong,
+    #[error("The song is already there!")]
+    SamePosition,
+    #[error("The range has to start before it ends!")]
+    InvalidRange,
+    #[error("There are no songs to remove!")]
+    NothingToRemove,
+}
+
+impl QueueEditable for TrackQueue {
+    fn move_track(&self, from: usize, to: usize) -> Result<(), QueueEditError> {
+        self.modify_queue(|q| {
+            if q.is_empty() {
+                return Err(QueueEditError::NothingIsPlaying);
+            }
+
+            if from < 1 || to < 1 || q.len() < from || q.len() < to {
+                return Err(QueueEditError::IndexOutOfBounds);
+            }
+
+            if from == 1 || to == 1 {
+                return Err(QueueEditError::CannotEditCurrentSong);
+            }
+
+            if from == to {
+                return Err(QueueEditError::SamePosition);
+            }
+
+            let track = q.remove(from - 1).expect("Checked that it's in the queue");
+            q.insert(to - 1, track);
+
+            Ok(())
+        })
+    }
+
+    fn remove_range(&self, from: usize, to: usize) -> Result<Vec<Queued>, QueueEditError> {
+        self.modify_queue(|q| {
+            if q.is_empty() {
+                return Err(QueueEditError::NothingIsPlaying);
+            }
+
+            if from < 1 || q.len() < to {
+                return Err(QueueEditError::IndexOutOfBounds);
+            }
+
+            if from == 1 {
+                return Err(QueueEditError::CannotEditCurrentSong);
+            }
+
+            if from > to {
+                return Err(QueueEditError::InvalidRange);
+            }
+
+            Ok(q.drain(from - 1..to).collect())
+        })
+    }
+
+    fn remove_upcoming(
+        &self,
+        filter: impl Fn(&Queued) -> bool,
+    ) -> Result<Vec<Queued>, QueueEditError> {
+        self.modify_queue(|q| {
+            let Some(current) = q.pop_front() else {
+                return Err(QueueEditError::NothingIsPlaying);
+            };
+
+            let (removed, kept): (Vec<Queued>, Vec<Queued>) =
+                q.drain(..).partition(|track| filter(track));
+            *q = kept.into();
+            q.push_front(current);
+
+            if removed.is_empty() {
+                Err(QueueEditError::NothingToRemove)
+            } else {
+                Ok(removed)
+            }
+        })
+    }
+}
+
+/// Stops tracks that were taken out of the queue and forgets their data
+pub async fn discard_tracks(ctx: &Context, tracks: Vec<Queued>) {
+    let track_data = get_track_data_lock(&ctx.data).await;
+
+    for track in tracks {
+        // Removed first so the track ending isn't treated like a finished song
+        track_data.remove(&track.uuid());
+        let _ = track.stop();
+    }
+}
+
+/// Move a song to another position in the queue
+pub async fn move_song(ctx: &Context, command: &ApplicationCommandInteraction) {
+    let guild_id = command.guild_id.expect("Couldn't get guild ID");
+
+    let (Some(from), Some(to)) = (get_position(command, "from"), get_position(command, "to"))
+    else {
+        respond_with_content(ctx, command, "Invalid number!").await;
+        return;
+    };
+
+    if let ControlFlow::Break(()) = respond_if_not_dj(ctx, command).await {
+        return;
+    }
+
+    if let ControlFlow::Break(()) = respond_if_not_same_vc(guild_id, ctx, command).await {
+        return;
+    }
+
+    let Some(call_lock) = get_call_lock(ctx, guild_id, command).await else {
+        return;
+    };
+    let call = call_lock.lock().await;
+
+    let response = match call.queue().move_track(from, to) {
+        Ok(()) => format!("Moved track {from} to {to}."),
+        Err(why) => why.to_string(),
+    };
+    save_session(ctx, guild_id, call.queue()).await;
+
+    respond_with_content(ctx, command, response).await;
+}
+
+/// Remove a song or a range of songs from the queue
+pub async fn remove_songs(ctx: &Context, command: &ApplicationCommandInteraction) {
+    let guild_id = command.guild_id.expect("Couldn't get guild ID");
+
+    let Some(from) = get_position(command, "from") else {
+        respond_with_content(ctx, command, "Invalid number!").await;
+        return;
+    };
+    let to = get_position(command, "to").unwrap_or(from);
+
+    if let ControlFlow::Break(()) = respond_if_not_dj(ctx, command).await {
+        return;
+    }
+
+    if let ControlFlow::Break(()) = respond_if_not_same_vc(guild_id, ctx, command).await {
+        return;
+    }
+
+    let Some(call_lock) = get_call_lock(ctx, guild_id, command).await else {
+        return;
+    };
+    let call = call_lock.lock().await;
+
+    let removed = call.queue().remove_range(from, to);
+    save_session(ctx, guild_id, call.queue()).await;
+    respond_with_removed(ctx, command, removed).await;
+}
+
+/// Remove every upcoming song a user added, or the ones the user who invoked the command added.
+///
+/// Users can always remove their own songs
+pub async fn remove_songs_by(ctx: &Context, command: &ApplicationCommandInteraction) {
+    let guild_id = command.guild_id.expect("Couldn't get guild ID");
+
+    let user = match get_option(command, "user") {
+        Some(CommandDataOptionValue::User(user, _)) => user,
+        _ => &command.user,
+    };
+
+    if user.id != command.user.id {
+        if let ControlFlow::Break(()) = respond_if_not_dj(ctx, command).await {
+            return;
+        }
+    }
+
+    if let ControlFlow::Break(()) = respond_if_not_same_vc(guild_id, ctx, command).await {
+        return;
+    }
+
+    let Some(call_lock) = get_call_lock(ctx, guild_id, command).await else {
+        return;
+    };
+    let call = call_lock.lock().await;
+
+    let track_data = get_track_data_lock(&ctx.data).await;
+    let removed = call.queue().remove_upcoming(|track| {
+        track_data
+            .get(&track.uuid())
+            .is_some_and(|data| data.requester == Some(user.id))
+    });
+    drop(track_data);
+    save_session(ctx, guild_id, call.queue()).await;
+
+    respond_with_removed(ctx, command, removed).await;
+}
+
+/// Remove every song except the one that's playing
+pub async fn clear_queue(ctx: &Context, command: &ApplicationCommandInteraction) {
+    let guild_id = command.guild_id.expect("Couldn't get guild ID");
+
+    if let ControlFlow::Break(()) = respond_if_not_dj(ctx, command).await {
+        return;
+    }
+
+    if let ControlFlow::Break(()) = respond_if_not_same_vc(guild_id, ctx, command).await {
+        return;
+    }
+
+    let Some(call_lock) = get_call_lock(ctx, guild_id, command).await else {
+        return;
+    };
+    let call = call_lock.lock().await;
+
+    let removed = call.queue().remove_upcoming(|_| true);
+    save_session(ctx, guild_id, call.queue()).await;
+    respond_with_removed(ctx, command, removed).await;
+}
+
+async fn respond_with_removed(
+    ctx: &Context,
+    command: &ApplicationCommandInteraction,
+    removed: Result<Vec<Queued>, QueueEditError>,
+) {
+    let response = match removed {
+        Ok(tracks) => {
+            let response = match tracks.len() {
+                1 => "Removed 1 song from the queue.".to_owned(),
+                count => format!("Removed {count} songs from the queue."),
+            };
+            discard_tracks(ctx, tracks).await;
+            response
+        }
+        Err(why) => why.to_string(),
+    };
+
+    respond_with_content(ctx, command, response).await;
+}
+
+fn get_position(command: &ApplicationCommandInteraction, name: &str) -> Option<usize> {
+    match get_option(command, name)? {
+        CommandDataOptionValue::Integer(position) => usize::try_from(*position).ok(),
+        _ => None,
+    }
+}
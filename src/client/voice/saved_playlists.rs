@@ -0,0 +1,653 @@
+use std::time::Duration;
+
+use serenity::{
+    client::Context,
+    model::{
+        id::{GuildId, UserId},
+        prelude::interaction::{
+            application_command::{ApplicationCommandInteraction, CommandDataOptionValue},
+            autocomplete::AutocompleteInteraction,
+        },
+    },
+    utils::Colour,
+};
+use songbird::tracks::TrackQueue;
+use thiserror::Error;
+
+use super::{
+    global_data::{get_saved_playlists_lock, save_saved_playlists, SavedPlaylist, SavedTrack},
+    helper_funcs::format_duration,
+    play::{enqueue_input_unsaved, join_command_call},
+    playlist::{get_playlist_entries, is_playlist_url, max_playlist_tracks},
+    sessions::save_session,
+    sources::resolve_source,
+};
+use crate::client::helper_funcs::{get_option, respond_with_content, MAX_AUTOCOMPLETE_CHOICES};
+
+/// Discord doesn't allow autocomplete choices longer than 100 characters
+const MAX_PLAYLIST_NAME_LENGTH: usize = 100;
+/// How many songs `/playlist list` shows before cutting the list off
+const MAX_LISTED_TRACKS: usize = 20;
+/// How many playlists `/playlist list` shows, names are short enough for this many to fit in an embed
+const MAX_LISTED_PLAYLISTS: usize = 25;
+/// The most songs a saved playlist can have, so one user can't fill the playlists file
+const MAX_SAVED_PLAYLIST_TRACKS: usize = 200;
+
+#[derive(Debug, Error)]
+pub enum SavedPlaylistError {
+    #[error("You don't have a playlist called {0}")]
+    NotFound(String),
+    #[error("There's no playlist called {0}")]
+    NotPlayable(String),
+    #[error("You already have a playlist called {0}")]
+    AlreadyExists(String),
+    #[error("The name can't be empty or longer than {MAX_PLAYLIST_NAME_LENGTH} characters")]
+    InvalidName,
+    #[error("Playlists can only be shared in a server")]
+    NotInGuild,
+    #[error("There's no song {0} in the playlist")]
+    NoSuchTrack(usize),
+    #[error("The playlist is empty")]
+    Empty,
+    #[error("Nothing is queued")]
+    QueueEmpty,
+    #[error("Couldn't find the song")]
+    SongNotFound,
+    #[error("Playlists can't have more than {MAX_SAVED_PLAYLIST_TRACKS} songs")]
+    TooManyTracks,
+    #[error("Something went wrong while saving the playlist")]
+    CouldntSave(#[from] std::io::Error),
+}
+
+/// Create, change and play the playlists users saved
+pub async fn playlist(ctx: &Context, command: &ApplicationCommandInteraction) {
+    let subcommand = command
+        .data
+        .options
+        .first()
+        .map(|subcommand| subcommand.name.as_str());
+
+    let response = match subcommand {
+        Some("create") => create_playlist(ctx, command).await,
+        Some("add") => {
+            command.defer(&ctx.http).await.unwrap();
+            let response = add_to_playlist(ctx, command).await;
+            edit_response(ctx, command, response).await;
+            return;
+        }
+        Some("remove") => remove_from_playlist(ctx, command).await,
+        Some("list") => {
+            list_playlists(ctx, command).await;
+            return;
+        }
+        Some("play") => {
+            play_playlist(ctx, command).await;
+            return;
+        }
+        Some("delete") => delete_playlist(ctx, command).await,
+        Some("save-queue") => save_queue_to_playlist(ctx, command).await,
+        _ => {
+            respond_with_content(ctx, command, "Something went wrong").await;
+            return;
+        }
+    };
+
+    match response {
+        Ok(response) => respond_with_content(ctx, command, response).await,
+        Err(why) => respond_with_content(ctx, command, why).await,
+    }
+}
+
+/// Suggests the playlists the user can play whose names contain what they have typed so far
+pub async fn autocomplete_saved_playlists(ctx: &Context, autocomplete: &AutocompleteInteraction) {
+    let input = autocomplete
+        .data
+        .options
+        .iter()
+        .flat_map(|option| std::iter::once(option).chain(option.options.iter()))
+        .find(|option| option.focused)
+        .and_then(|option| option.value.as_ref())
+        .and_then(|value| value.as_str())
+        .unwrap_or_default()
+        .to_lowercase();
+
+    let mut names: Vec<String> =
+        get_playable_playlists(ctx, autocomplete.user.id, autocomplete.guild_id)
+            .await
+            .into_iter()
+            .map(|(_, playlist)| playlist.name)
+            .filter(|name| name.to_lowercase().contains(input.trim()))
+            .collect();
+    names.sort_unstable();
+    names.dedup();
+
+    autocomplete
+        .create_autocomplete_response(&ctx.http, |r| {
+            for name in names.into_iter().take(MAX_AUTOCOMPLETE_CHOICES) {
+                r.add_string_choice(&name, &name);
+            }
+            r
+        })
+        .await
+        .expect("Error creating autocomplete response");
+}
+
+async fn create_playlist(
+    ctx: &Context,
+    command: &ApplicationCommandInteraction,
+) -> Result<String, SavedPlaylistError> {
+    let name = get_name_option(command, "name")?;
+    let shared_guild = get_shared_guild(command)?;
+
+    add_playlist(
+        ctx,
+        command.user.id,
+        SavedPlaylist {
+            name: name.clone(),
+            shared_guild,
+            tracks: Vec::new(),
+        },
+    )
+    .await?;
+
+    Ok(format!("Created the playlist **{name}**"))
+}
+
+/// Adds a song, or every song of a playlist link, to the end of the playlist
+async fn add_to_playlist(
+    ctx: &Context,
+    command: &ApplicationCommandInteraction,
+) -> Result<String, SavedPlaylistError> {
+    let name = get_name_option(command, "playlist")?;
+    let Some(CommandDataOptionValue::String(query)) = get_option(command, "query") else {
+        return Err(SavedPlaylistError::SongNotFound);
+    };
+
+    // Checked before looking the songs up so it doesn't take long to find out there's no playlist
+    if find_own_playlist(ctx, command.user.id, &name)
+        .await
+        .is_none()
+    {
+        return Err(SavedPlaylistError::NotFound(name));
+    }
+
+    let tracks: Vec<SavedTrack> = if is_playlist_url(query) {
+        let (entries, _) = get_playlist_entries(&ctx.data, query, max_playlist_tracks())
+            .await
+            .map_err(|_| SavedPlaylistError::SongNotFound)?;
+
+        entries
+            .into_iter()
+            .map(|entry| SavedTrack {
+                source: entry.url,
+                title: entry.title,
+                channel: entry.channel,
+                duration: entry.duration,
+                requester: None,
+            })
+            .collect()
+    } else {
+        let input = resolve_source(&ctx.data, query, false)
+            .await
+            .map_err(|_| SavedPlaylistError::SongNotFound)?;
+
+        // resolve_source always sets the source url, so this never skips the song
+        SavedTrack::from_metadata(&input.metadata, None)
+            .into_iter()
+            .collect()
+    };
+
+    let added = tracks.len();
+    let response = match tracks.first().and_then(|track| track.title.clone()) {
+        Some(title) if added == 1 => format!("Added **{title}** to **{name}**"),
+        _ => format!("Added {added} songs to **{name}**"),
+    };
+
+    edit_own_playlist(ctx, command.user.id, &name, |playlist| {
+        playlist.tracks.extend(tracks);
+        Ok(())
+    })
+    .await?;
+
+    Ok(response)
+}
+
+async fn remove_from_playlist(
+    ctx: &Context,
+    command: &ApplicationCommandInteraction,
+) -> Result<String, SavedPlaylistError> {
+    let name = get_name_option(command, "playlist")?;
+    let position = match get_option(command, "position") {
+        Some(CommandDataOptionValue::Integer(position)) => usize::try_from(*position).unwrap_or(0),
+        _ => 0,
+    };
+
+    let removed = edit_own_playlist(ctx, command.user.id, &name, |playlist| {
+        if position == 0 || position > playlist.tracks.len() {
+            return Err(SavedPlaylistError::NoSuchTrack(position));
+        }
+
+        Ok(playlist.tracks.remove(position - 1))
+    })
+    .await?;
+
+    Ok(format!(
+        "Removed **{}** from **{name}**",
+        removed.title.unwrap_or(removed.source)
+    ))
+}
+
+async fn delete_playlist(
+    ctx: &Context,
+    command: &ApplicationCommandInteraction,
+) -> Result<String, SavedPlaylistError> {
+    let name = get_name_option(command, "playlist")?;
+
+    let saved_playlists = get_saved_playlists_lock(&ctx.data).await;
+    {
+        let mut playlists = saved_playlists.entry(command.user.id.0).or_default();
+        let Some(index) = playlists
+            .iter()
+            .position(|playlist| playlist.name.eq_ignore_ascii_case(&name))
+        else {
+            return Err(SavedPlaylistError::NotFound(name));
+        };
+        playlists.remove(index);
+    }
+    saved_playlists.remove_if(&command.user.id.0, |_, playlists| playlists.is_empty());
+    save_saved_playlists(&saved_playlists)?;
+
+    Ok(format!("Deleted the playlist **{name}**"))
+}
+
+/// Saves every song in the queue to the playlist, replacing its songs if the user already has one with that name
+async fn save_queue_to_playlist(
+    ctx: &Context,
+    command: &ApplicationCommandInteraction,
+) -> Result<String, SavedPlaylistError> {
+    let name = get_name_option(command, "name")?;
+    let shared_guild = get_shared_guild(command)?;
+    let guild_id = command.guild_id.expect("Couldn't get guild ID");
+
+    let manager = songbird::get(ctx).await.expect("songbird error").clone();
+    let tracks = match manager.get(guild_id) {
+        Some(call_lock) => get_queue_tracks(call_lock.lock().await.queue()),
+        None => Vec::new(),
+    };
+
+    if tracks.is_empty() {
+        return Err(SavedPlaylistError::QueueEmpty);
+    }
+    let count = tracks.len();
+
+    if find_own_playlist(ctx, command.user.id, &name)
+        .await
+        .is_some()
+    {
+        edit_own_playlist(ctx, command.user.id, &name, |playlist| {
+            playlist.tracks = tracks;
+            playlist.shared_guild = shared_guild;
+            Ok(())
+        })
+        .await?;
+
+        return Ok(format!(
+            "Replaced the songs of **{name}** with the {count} songs in the queue"
+        ));
+    }
+
+    add_playlist(
+        ctx,
+        command.user.id,
+        SavedPlaylist {
+            name: name.clone(),
+            shared_guild,
+            tracks,
+        },
+    )
+    .await?;
+
+    Ok(format!(
+        "Saved the {count} songs in the queue to **{name}**"
+    ))
+}
+
+/// Shows the playlists the user can play, or the songs of one of them
+async fn list_playlists(ctx: &Context, command: &ApplicationCommandInteraction) {
+    let colour = Colour::from_rgb(149, 8, 2);
+
+    let embed_result = match get_option(command, "playlist") {
+        Some(CommandDataOptionValue::String(name)) => {
+            match find_playable_playlist(ctx, command.user.id, command.guild_id, name).await {
+                Some((owner, playlist)) => Ok((
+                    playlist.name.clone(),
+                    describe_tracks(&playlist.tracks),
+                    Some(owner),
+                )),
+                None => Err(SavedPlaylistError::NotPlayable(name.clone())),
+            }
+        }
+        _ => Ok((
+            "Playlists".to_owned(),
+            describe_playlists(ctx, command).await,
+            None,
+        )),
+    };
+
+    let (title, description, owner) = match embed_result {
+        Ok(embed) => embed,
+        Err(why) => {
+            respond_with_content(ctx, command, why).await;
+            return;
+        }
+    };
+
+    command
+        .create_interaction_response(&ctx.http, |r| {
+            r.interaction_response_data(|d| {
+                d.embed(|e| {
+                    e.title(title).description(description).colour(colour);
+                    if let Some(owner) = owner {
+                        e.field("Made by", format!("<@{owner}>"), true);
+                    }
+                    e
+                })
+            })
+        })
+        .await
+        .expect("Error creating interaction response");
+}
+
+/// Queues every song of the playlist
+async fn play_playlist(ctx: &Context, command: &ApplicationCommandInteraction) {
+    let guild_id = command.guild_id.expect("Couldn't get guild ID");
+    let name = match get_name_option(command, "playlist") {
+        Ok(name) => name,
+        Err(why) => {
+            respond_with_content(ctx, command, why).await;
+            return;
+        }
+    };
+
+    let Some((_, playlist)) =
+        find_playable_playlist(ctx, command.user.id, command.guild_id, &name).await
+    else {
+        respond_with_content(ctx, command, SavedPlaylistError::NotPlayable(name)).await;
+        return;
+    };
+    if playlist.tracks.is_empty() {
+        respond_with_content(ctx, command, SavedPlaylistError::Empty).await;
+        return;
+    }
+
+    command.defer(&ctx.http).await.unwrap();
+
+    let Some(call_lock) = join_command_call(ctx, command).await else {
+        return;
+    };
+    let mut call = call_lock.lock().await;
+
+    let mut queued = 0;
+    let mut total_duration = Duration::ZERO;
+    for track in playlist.tracks {
+        let Some(input) = track.into_lazy_input(&ctx.data).await else {
+            continue;
+        };
+
+        total_duration += input.metadata.duration.unwrap_or_default();
+        enqueue_input_unsaved(ctx, &mut call, guild_id, input, Some(command.user.id)).await;
+        queued += 1;
+    }
+    save_session(ctx, guild_id, call.queue()).await;
+
+    let colour = Colour::from_rgb(149, 8, 2);
+
+    command
+        .edit_original_interaction_response(&ctx.http, |r| {
+            r.embed(|e| {
+                e.title(format!("Queued {queued} songs"))
+                    .description(format!("From the playlist **{}**", playlist.name))
+                    .colour(colour)
+                    .field("Length: ", format_duration(total_duration), true)
+                    .field("Songs in queue: ", call.queue().len(), true)
+            })
+        })
+        .await
+        .expect("Error creating interaction response");
+}
+
+async fn add_playlist(
+    ctx: &Context,
+    owner: UserId,
+    playlist: SavedPlaylist,
+) -> Result<(), SavedPlaylistError> {
+    if playlist.tracks.len() > MAX_SAVED_PLAYLIST_TRACKS {
+        return Err(SavedPlaylistError::TooManyTracks);
+    }
+
+    let saved_playlists = get_saved_playlists_lock(&ctx.data).await;
+    {
+        let mut playlists = saved_playlists.entry(owner.0).or_default();
+        if playlists
+            .iter()
+            .any(|saved| saved.name.eq_ignore_ascii_case(&playlist.name))
+        {
+            return Err(SavedPlaylistError::AlreadyExists(playlist.name));
+        }
+        playlists.push(playlist);
+    }
+    save_saved_playlists(&saved_playlists)?;
+
+    Ok(())
+}
+
+/// Changes one of the user's own playlists and saves it if the change succeeded.
+///
+/// The change is undone if it leaves the playlist with too many songs
+async fn edit_own_playlist<T>(
+    ctx: &Context,
+    owner: UserId,
+    name: &str,
+    edit: impl FnOnce(&mut SavedPlaylist) -> Result<T, SavedPlaylistError>,
+) -> Result<T, SavedPlaylistError> {
+    let saved_playlists = get_saved_playlists_lock(&ctx.data).await;
+    let result = {
+        let Some(mut playlists) = saved_playlists.get_mut(&owner.0) else {
+            return Err(SavedPlaylistError::NotFound(name.to_owned()));
+        };
+        let Some(playlist) = playlists
+            .iter_mut()
+            .find(|playlist| playlist.name.eq_ignore_ascii_case(name))
+        else {
+            return Err(SavedPlaylistError::NotFound(name.to_owned()));
+        };
+        let original = playlist.clone();
+        let result = edit(playlist)?;
+        // Playlists saved before the limit existed can still have songs removed
+        let added_tracks = playlist.tracks.len() > original.tracks.len();
+        if added_tracks && playlist.tracks.len() > MAX_SAVED_PLAYLIST_TRACKS {
+            *playlist = original;
+            return Err(SavedPlaylistError::TooManyTracks);
+        }
+
+        result
+    };
+    save_saved_playlists(&saved_playlists)?;
+
+    Ok(result)
+}
+
+async fn find_own_playlist(ctx: &Context, owner: UserId, name: &str) -> Option<SavedPlaylist> {
+    get_saved_playlists_lock(&ctx.data)
+        .await
+        .get(&owner.0)?
+        .iter()
+        .find(|playlist| playlist.name.eq_ignore_ascii_case(name))
+        .cloned()
+}
+
+/// Finds the user's own playlist with the name, or one that was shared in the guild
+async fn find_playable_playlist(
+    ctx: &Context,
+    user_id: UserId,
+    guild_id: Option<GuildId>,
+    name: &str,
+) -> Option<(u64, SavedPlaylist)> {
+    get_playable_playlists(ctx, user_id, guild_id)
+        .await
+        .into_iter()
+        .find(|(_, playlist)| playlist.name.eq_ignore_ascii_case(name))
+}
+
+/// The user's own playlists followed by the ones shared in the guild, with their owners
+async fn get_playable_playlists(
+    ctx: &Context,
+    user_id: UserId,
+    guild_id: Option<GuildId>,
+) -> Vec<(u64, SavedPlaylist)> {
+    let saved_playlists = get_saved_playlists_lock(&ctx.data).await;
+
+    let own = saved_playlists
+        .get(&user_id.0)
+        .map(|playlists| playlists.clone())
+        .unwrap_or_default()
+        .into_iter()
+        .map(|playlist| (user_id.0, playlist));
+
+    let shared: Vec<(u64, SavedPlaylist)> = saved_playlists
+        .iter()
+        .filter(|playlists| *playlists.key() != user_id.0)
+        .flat_map(|playlists| {
+            let owner = *playlists.key();
+            playlists
+                .iter()
+                .filter(|playlist| {
+                    guild_id.is_some_and(|guild_id| playlist.shared_guild == Some(guild_id.0))
+                })
+                .map(|playlist| (owner, playlist.clone()))
+                .collect::<Vec<_>>()
+        })
+        .collect();
+
+    own.chain(shared).collect()
+}
+
+async fn describe_playlists(ctx: &Context, command: &ApplicationCommandInteraction) -> String {
+    let playlists = get_playable_playlists(ctx, command.user.id, command.guild_id).await;
+
+    if playlists.is_empty() {
+        return "There are no playlists yet, make one with /playlist create".to_owned();
+    }
+
+    let mut description = playlists
+        .iter()
+        .take(MAX_LISTED_PLAYLISTS)
+        .map(|(owner, playlist)| {
+            let made_by = if *owner == command.user.id.0 {
+                if playlist.shared_guild.is_some() {
+                    "yours, shared".to_owned()
+                } else {
+                    "yours".to_owned()
+                }
+            } else {
+                format!("by <@{owner}>")
+            };
+            format!(
+                "**{}** - {} songs, {made_by}",
+                playlist.name,
+                playlist.tracks.len()
+            )
+        })
+        .collect::<Vec<String>>()
+        .join("\n");
+
+    if playlists.len() > MAX_LISTED_PLAYLISTS {
+        let more = playlists.len() - MAX_LISTED_PLAYLISTS;
+        description = format!("{description}\nand {more} more");
+    }
+
+    description
+}
+
+fn describe_tracks(tracks: &[SavedTrack]) -> String {
+    if tracks.is_empty() {
+        return "The playlist is empty, add songs with /playlist add".to_owned();
+    }
+
+    let mut description = tracks
+        .iter()
+        .take(MAX_LISTED_TRACKS)
+        .enumerate()
+        .map(|(index, track)| {
+            format!(
+                "{}. {} | {}",
+                index + 1,
+                track.title.as_ref().unwrap_or(&track.source),
+                track
+                    .duration
+                    .map_or_else(|| "Unknown".to_owned(), format_duration)
+            )
+        })
+        .collect::<Vec<String>>()
+        .join("\n");
+
+    if tracks.len() > MAX_LISTED_TRACKS {
+        let more = tracks.len() - MAX_LISTED_TRACKS;
+        description = format!("{description}\nand {more} more");
+    }
+
+    description
+}
+
+/// The songs of the queue that can be saved, starting with the one that's playing
+fn get_queue_tracks(queue: &TrackQueue) -> Vec<SavedTrack> {
+    queue
+        .current_queue()
+        .iter()
+        .filter_map(|track| SavedTrack::from_metadata(track.metadata(), None))
+        .collect()
+}
+
+fn get_name_option(
+    command: &ApplicationCommandInteraction,
+    option: &str,
+) -> Result<String, SavedPlaylistError> {
+    match get_option(command, option) {
+        Some(CommandDataOptionValue::String(name))
+            if !name.trim().is_empty()
+                && name.trim().chars().count() <= MAX_PLAYLIST_NAME_LENGTH =>
+        {
+            Ok(name.trim().to_owned())
+        }
+        _ => Err(SavedPlaylistError::InvalidName),
+    }
+}
+
+/// The guild the playlist is shared in if the `shared` option is set
+fn get_shared_guild(
+    command: &ApplicationCommandInteraction,
+) -> Result<Option<u64>, SavedPlaylistError> {
+    match get_option(command, "shared") {
+        Some(CommandDataOptionValue::Boolean(true)) => command
+            .guild_id
+            .map(|guild_id| Some(guild_id.0))
+            .ok_or(SavedPlaylistError::NotInGuild),
+        _ => Ok(None),
+    }
+}
+
+async fn edit_response(
+    ctx: &Context,
+    command: &ApplicationCommandInteraction,
+    response: Result<String, SavedPlaylistError>,
+) {
+    let content = match response {
+        Ok(response) => response,
+        Err(why) => why.to_string(),
+    };
+
+    command
+        .edit_original_interaction_response(&ctx.http, |r| r.content(content))
+        .await
+        .expect("Error creating interaction response");
+}
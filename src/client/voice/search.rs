@@ -0,0 +1,257 @@
+use std::time::Duration;
+
+use serenity::{
+    builder::CreateComponents,
+    client::Context,
+    model::{
+        prelude::{
+            component::ButtonStyle,
+            interaction::{
+                application_command::{ApplicationCommandInteraction, CommandDataOptionValue},
+                message_component::MessageComponentInteraction,
+                InteractionResponseType, MessageFlags,
+            },
+        },
+        Timestamp,
+    },
+    utils::Colour,
+};
+
+use super::{
+    helper_funcs::format_duration,
+    play::{create_queued_response, enqueue_input, join_call},
+    playlist::{get_playlist_entries, PlaylistEntry},
+    sources::resolve_source,
+};
+use crate::client::{
+    helper_funcs::{
+        get_option, respond_ephemeral, respond_with_content, truncate, MAX_EMBED_TITLE_LENGTH,
+    },
+    ButtonIds,
+};
+
+/// How many results the search shows
+const SEARCH_RESULTS: usize = 5;
+/// Keeps the query short enough to fit in the title of the results
+pub const MAX_SEARCH_QUERY_LENGTH: u16 = 200;
+/// Select menu labels and descriptions can't be longer than 100 characters
+const MAX_OPTION_LENGTH: usize = 100;
+/// How long the results can be picked from before the menu is removed
+const SEARCH_TIMEOUT: Duration = Duration::from_secs(90);
+
+/// Lists the top youtube results for the query with a menu to pick which one to play
+pub async fn search(ctx: &Context, command: &ApplicationCommandInteraction) {
+    let Some(CommandDataOptionValue::String(query)) = get_option(command, "query") else {
+        respond_with_content(ctx, command, "Something went wrong").await;
+        return;
+    };
+
+    command.defer(&ctx.http).await.unwrap();
+
+    let results = get_playlist_entries(
+        &ctx.data,
+        &format!("ytsearch{SEARCH_RESULTS}:{query}"),
+        SEARCH_RESULTS,
+    )
+    .await;
+    let results = match results {
+        Ok((results, _)) => results,
+        Err(why) => {
+            println!("Err searching youtube: {why:?}");
+            command
+                .edit_original_interaction_response(&ctx.http, |r| {
+                    r.content("Couldn't find anything on Youtube")
+                })
+                .await
+                .expect("Error creating interaction response");
+            return;
+        }
+    };
+
+    let colour = Colour::from_rgb(149, 8, 2);
+    command
+        .edit_original_interaction_response(&ctx.http, |r| {
+            r.embed(|e| {
+                e.title(truncate(
+                    &format!("Results for {query}"),
+                    MAX_EMBED_TITLE_LENGTH,
+                ))
+                .description("Pick the song to play, only you can choose")
+                .colour(colour);
+                for (i, result) in results.iter().enumerate() {
+                    e.field(
+                        format!("{}. {}", i + 1, result.title.as_deref().unwrap_or_default()),
+                        describe_result(result),
+                        false,
+                    );
+                }
+                e
+            })
+            .components(|c| create_search_components(c, &results))
+        })
+        .await
+        .expect("Error creating interaction response");
+
+    // Interaction tokens last long enough for the menu to be removed afterwards
+    let command = command.clone();
+    let ctx = ctx.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(SEARCH_TIMEOUT).await;
+
+        // The menu is already gone if a result was picked or the search was cancelled
+        let Ok(message) = command.get_interaction_response(&ctx.http).await else {
+            return;
+        };
+        if message.components.is_empty() {
+            return;
+        }
+
+        if let Err(why) = command
+            .edit_original_interaction_response(&ctx.http, |r| {
+                r.content("The search expired")
+                    .set_embeds(Vec::new())
+                    .components(|c| c)
+            })
+            .await
+        {
+            eprintln!("Couldn't remove the search menu: {why}");
+        }
+    });
+}
+
+/// Queues the result that was picked from the [`search`] menu
+pub async fn pick_search_result(ctx: &Context, menu: &MessageComponentInteraction) {
+    if let Some(reason) = cant_use_menu(menu) {
+        respond_ephemeral(ctx, menu, reason).await;
+        return;
+    }
+
+    let guild_id = menu.guild_id.expect("Couldn't get guild ID");
+    let Some(url) = menu.data.values.first() else {
+        respond_ephemeral(ctx, menu, "Something went wrong").await;
+        return;
+    };
+
+    menu.create_interaction_response(&ctx.http, |r| {
+        r.kind(InteractionResponseType::DeferredUpdateMessage)
+    })
+    .await
+    .expect("Error creating interaction response");
+
+    let call_lock = match join_call(ctx, guild_id, menu.user.id, menu.channel_id).await {
+        Ok(call_lock) => call_lock,
+        Err(why) => {
+            respond_ephemeral_followup(ctx, menu, why).await;
+            return;
+        }
+    };
+    let mut call = call_lock.lock().await;
+
+    let source = match resolve_source(&ctx.data, url, false).await {
+        Ok(source) => source,
+        Err(why) => {
+            println!("Err starting source: {why:?}");
+            respond_ephemeral_followup(ctx, menu, "Couldn't find the video on Youtube").await;
+            return;
+        }
+    };
+
+    let metadata = enqueue_input(ctx, &mut call, guild_id, source, Some(menu.user.id)).await;
+    let (content, embed) = create_queued_response(&metadata, call.queue(), menu.user.id);
+
+    menu.edit_original_interaction_response(&ctx.http, |r| {
+        r.content(content).set_embeds(vec![embed]).components(|c| c)
+    })
+    .await
+    .expect("Error creating interaction response");
+}
+
+/// Removes the menu without playing anything
+pub async fn cancel_search(ctx: &Context, button: &MessageComponentInteraction) {
+    if let Some(reason) = cant_use_menu(button) {
+        respond_ephemeral(ctx, button, reason).await;
+        return;
+    }
+
+    button
+        .create_interaction_response(&ctx.http, |r| {
+            r.kind(InteractionResponseType::UpdateMessage)
+                .interaction_response_data(|d| {
+                    d.content("The search was cancelled")
+                        .set_embeds(Vec::new())
+                        .components(|c| c)
+                })
+        })
+        .await
+        .expect("Error creating interaction response");
+}
+
+/// Only the user who searched can pick a result, and only before the search expires
+fn cant_use_menu(component: &MessageComponentInteraction) -> Option<&'static str> {
+    let searcher = component
+        .message
+        .interaction
+        .as_ref()
+        .map(|interaction| interaction.user.id);
+    if searcher != Some(component.user.id) {
+        return Some("Only the user who searched can pick a result");
+    }
+
+    let age = Timestamp::now().unix_timestamp() - component.message.timestamp.unix_timestamp();
+    if age.unsigned_abs() > SEARCH_TIMEOUT.as_secs() {
+        return Some("The search expired");
+    }
+
+    None
+}
+
+fn create_search_components<'a>(
+    c: &'a mut CreateComponents,
+    results: &[PlaylistEntry],
+) -> &'a mut CreateComponents {
+    c.create_action_row(|a| {
+        a.create_select_menu(|m| {
+            m.custom_id(ButtonIds::SearchResultPicker)
+                .placeholder("Pick a song")
+                .options(|o| {
+                    for (i, result) in results.iter().enumerate() {
+                        o.create_option(|o| {
+                            let title = result.title.as_deref().unwrap_or_default();
+                            o.label(truncate(&format!("{}. {title}", i + 1), MAX_OPTION_LENGTH))
+                                .description(truncate(&describe_result(result), MAX_OPTION_LENGTH))
+                                .value(&result.url)
+                        });
+                    }
+                    o
+                })
+        })
+    })
+    .create_action_row(|a| {
+        a.create_button(|b| {
+            b.label("Cancel")
+                .style(ButtonStyle::Secondary)
+                .custom_id(ButtonIds::SearchCancel)
+        })
+    })
+}
+
+fn describe_result(result: &PlaylistEntry) -> String {
+    format!(
+        "{} | {}",
+        result.channel.as_deref().unwrap_or_default(),
+        format_duration(result.duration.unwrap_or_default())
+    )
+}
+
+async fn respond_ephemeral_followup(
+    ctx: &Context,
+    component: &MessageComponentInteraction,
+    content: impl ToString,
+) {
+    component
+        .create_followup_message(&ctx.http, |m| {
+            m.content(content).flags(MessageFlags::EPHEMERAL)
+        })
+        .await
+        .expect("Error creating interaction response");
+}
@@ -0,0 +1,205 @@
+use std::{ops::ControlFlow, time::Duration};
+
+use super::{
+    helper_funcs::{format_duration, get_call_lock, respond_if_not_same_vc},
+    permissions::respond_if_not_dj,
+};
+use crate::client::helper_funcs::{get_option, respond_with_content};
+use serenity::{
+    client::Context,
+    model::prelude::interaction::application_command::{
+        ApplicationCommandInteraction, CommandDataOptionValue,
+    },
+};
+use thiserror::Error;
+
+/// The reason the current track can't be seeked
+#[derive(Debug, Error)]
+pub enum SeekError {
+    #[error("use a time like 1:23 or 90s, or +30s and -1:00 to move from the current position")]
+    InvalidTime,
+    #[error("the song is only {} long", format_duration(*.0))]
+    PastEnd(Duration),
+    #[error("this song can't be seeked")]
+    NotSeekable,
+}
+
+/// Jumps to a time in the current track, or forwards and backwards from the current position
+pub async fn seek(ctx: &Context, command: &ApplicationCommandInteraction) {
+    let guild_id = command.guild_id.expect("Couldn't get guild ID");
+
+    let Some(CommandDataOptionValue::String(time)) = get_option(command, "time") else {
+        respond_with_content(ctx, command, "Something went wrong").await;
+        return;
+    };
+
+    if let ControlFlow::Break(()) = respond_if_not_dj(ctx, command).await {
+        return;
+    }
+
+    if let ControlFlow::Break(()) = respond_if_not_same_vc(guild_id, ctx, command).await {
+        return;
+    }
+
+    let Some(call_lock) = get_call_lock(ctx, guild_id, command).await else {
+        return;
+    };
+    let call = call_lock.lock().await;
+
+    let Some(track) = call.queue().current() else {
+        respond_with_content(ctx, command, "Nothing is playing.").await;
+        return;
+    };
+    let Ok(info) = track.get_info().await else {
+        respond_with_content(ctx, command, "Couldn't seek the song.").await;
+        return;
+    };
+
+    let seeked =
+        get_seek_position(time, info.position, track.metadata().duration).and_then(|position| {
+            if !track.is_seekable() {
+                return Err(SeekError::NotSeekable);
+            }
+            track
+                .seek_time(position)
+                .map(|()| position)
+                .map_err(|_| SeekError::NotSeekable)
+        });
+
+    let response = match seeked {
+        Ok(position) => format!("Jumped to {}", format_duration(position)),
+        Err(why) => format!("Couldn't seek the song, {why}"),
+    };
+
+    respond_with_content(ctx, command, response).await;
+}
+
+/// Where to seek to, `+` and `-` move relative to the current position
+fn get_seek_position(
+    time: &str,
+    position: Duration,
+    duration: Option<Duration>,
+) -> Result<Duration, SeekError> {
+    let time = time.trim();
+
+    let new_position = if let Some(offset) = time.strip_prefix('+') {
+        position
+            .checked_add(parse_time(offset).ok_or(SeekError::InvalidTime)?)
+            .ok_or(SeekError::InvalidTime)?
+    } else if let Some(offset) = time.strip_prefix('-') {
+        position.saturating_sub(parse_time(offset).ok_or(SeekError::InvalidTime)?)
+    } else {
+        parse_time(time).ok_or(SeekError::InvalidTime)?
+    };
+
+    match duration {
+        Some(duration) if new_position >= duration => Err(SeekError::PastEnd(duration)),
+        _ => Ok(new_position),
+    }
+}
+
+/// Parses `90`, `90s`, `2m`, `1:30` and `1:01:30`
+fn parse_time(time: &str) -> Option<Duration> {
+    let time = time.trim();
+
+    if let Some(seconds) = time.strip_suffix('s') {
+        return seconds.trim().parse().ok().map(Duration::from_secs);
+    }
+    if let Some(minutes) = time.strip_suffix('m') {
+        return minutes
+            .trim()
+            .parse::<u64>()
+            .ok()?
+            .checked_mul(60)
+            .map(Duration::from_secs);
+    }
+
+    let parts: Vec<&str> = time.split(':').collect();
+    if parts.len() > 3 {
+        return None;
+    }
+
+    let mut seconds: u64 = 0;
+    for (i, part) in parts.iter().enumerate() {
+        let part: u64 = part.parse().ok()?;
+        // Only the first part can be bigger than a minute, 1:75 isn't a time
+        if i > 0 && part >= 60 {
+            return None;
+        }
+        seconds = seconds.checked_mul(60)?.checked_add(part)?;
+    }
+
+    Some(Duration::from_secs(seconds))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{get_seek_position, parse_time, SeekError};
+    use std::time::Duration;
+
+    #[test]
+    fn times_are_parsed() {
+        assert_eq!(parse_time("90"), Some(Duration::from_secs(90)));
+        assert_eq!(parse_time("90s"), Some(Duration::from_secs(90)));
+        assert_eq!(parse_time("2m"), Some(Duration::from_mins(2)));
+        assert_eq!(parse_time("1:30"), Some(Duration::from_secs(90)));
+        assert_eq!(parse_time("1:01:30"), Some(Duration::from_secs(3690)));
+    }
+
+    #[test]
+    fn invalid_times_are_rejected() {
+        assert_eq!(parse_time("1:75"), None);
+        assert_eq!(parse_time("1:1:1:1"), None);
+        assert_eq!(parse_time("abc"), None);
+        assert_eq!(parse_time(""), None);
+        assert_eq!(parse_time("-5"), None);
+    }
+
+    #[test]
+    fn huge_times_dont_overflow() {
+        assert_eq!(parse_time("18446744073709551615m"), None);
+        assert_eq!(parse_time("18446744073709551615:00"), None);
+        assert!(matches!(
+            get_seek_position("+18446744073709551615s", Duration::from_secs(10), None),
+            Err(SeekError::InvalidTime)
+        ));
+    }
+
+    #[test]
+    fn seek_positions_are_relative_with_a_sign() {
+        let position = Duration::from_mins(1);
+        let duration = Some(Duration::from_mins(3));
+
+        assert_eq!(
+            get_seek_position("+30s", position, duration).unwrap(),
+            Duration::from_secs(90)
+        );
+        assert_eq!(
+            get_seek_position("-1:30", position, duration).unwrap(),
+            Duration::ZERO
+        );
+        assert_eq!(
+            get_seek_position("2:00", position, duration).unwrap(),
+            Duration::from_mins(2)
+        );
+    }
+
+    #[test]
+    fn seeking_past_the_end_is_rejected() {
+        let duration = Some(Duration::from_mins(3));
+
+        assert!(matches!(
+            get_seek_position("3:00", Duration::ZERO, duration),
+            Err(SeekError::PastEnd(_))
+        ));
+        assert!(matches!(
+            get_seek_position("+5m", Duration::ZERO, duration),
+            Err(SeekError::PastEnd(_))
+        ));
+        assert!(get_seek_position("1:00:00", Duration::ZERO, None).is_ok());
+        assert!(matches!(
+            get_seek_position("soon", Duration::ZERO, duration),
+            Err(SeekError::InvalidTime)
+        ));
+    }
+}
@@ -0,0 +1,374 @@
+use std::{
+    env,
+    str::FromStr,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use serenity::{
+    async_trait,
+    client::Context,
+    model::{
+        id::{ChannelId, GuildId, UserId},
+        prelude::{
+            component::ButtonStyle,
+            interaction::{
+                message_component::MessageComponentInteraction, InteractionResponseType,
+            },
+        },
+    },
+    prelude::Mutex,
+    utils::Colour,
+};
+use songbird::{tracks::TrackQueue, Call, EventContext, EventHandler};
+use strum_macros::EnumString;
+use thiserror::Error;
+
+use super::{
+    global_data::{
+        get_loop_modes_lock, get_queue_sessions_lock, get_track_data_lock, save_queue_sessions,
+        LoopMode, QueueSession, SavedTrack,
+    },
+    permissions::can_use_playback_button,
+    play::{add_queue_events, enqueue_input_unsaved},
+};
+use crate::client::{helper_funcs::respond_ephemeral, ButtonIds};
+
+/// How often the position in the current track is saved
+pub const SESSION_SAVE_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Sessions are only restored the first time the bot connects, not when it reconnects
+static SESSIONS_RESTORED: AtomicBool = AtomicBool::new(false);
+
+/// What happens to the saved queues when the bot starts, set with `RESTORE_QUEUES`
+#[derive(Default, PartialEq, Eq, EnumString)]
+enum RestoreMode {
+    /// A message with buttons to restore or discard the queue is sent where it was playing
+    #[default]
+    #[strum(serialize = "ask")]
+    Ask,
+    #[strum(serialize = "auto")]
+    Auto,
+    #[strum(serialize = "off")]
+    Off,
+}
+
+#[derive(Debug, Error)]
+pub enum RestoreError {
+    #[error("There's no queue to restore")]
+    NoSession,
+    #[error("Something is already playing")]
+    AlreadyPlaying,
+    #[error("Couldn't join the voice channel")]
+    CouldntJoin,
+    #[error("None of the songs could be played")]
+    NoPlayableTracks,
+}
+
+/// Saves the queue every [`SESSION_SAVE_INTERVAL`] so the position in the current track isn't lost
+pub struct SessionSaver {
+    pub call_lock: Arc<Mutex<Call>>,
+    pub guild_id: GuildId,
+    pub ctx: Context,
+}
+
+#[async_trait]
+impl EventHandler for SessionSaver {
+    async fn act(&self, _ctx: &EventContext<'_>) -> Option<songbird::Event> {
+        let call = self.call_lock.lock().await;
+        save_session(&self.ctx, self.guild_id, call.queue()).await;
+
+        None
+    }
+}
+
+/// Starts saving the queue of the guild, replacing the one that was saved before
+pub async fn start_session(
+    ctx: &Context,
+    guild_id: GuildId,
+    voice_channel: ChannelId,
+    text_channel: ChannelId,
+) {
+    get_queue_sessions_lock(&ctx.data).await.insert(
+        guild_id.0,
+        QueueSession {
+            voice_channel: voice_channel.0,
+            text_channel: text_channel.0,
+            tracks: Vec::new(),
+            position: Duration::ZERO,
+            loop_mode: LoopMode::Off,
+        },
+    );
+}
+
+/// Saves the queue of the guild, the session ends once the queue is empty
+pub async fn save_session(ctx: &Context, guild_id: GuildId, queue: &TrackQueue) {
+    let queue_sessions = get_queue_sessions_lock(&ctx.data).await;
+    if !queue_sessions.contains_key(&guild_id.0) {
+        return;
+    }
+
+    let position = match queue.current() {
+        Some(track) => track
+            .get_info()
+            .await
+            .map_or(Duration::ZERO, |info| info.position),
+        None => Duration::ZERO,
+    };
+    let loop_mode = get_loop_modes_lock(&ctx.data)
+        .await
+        .get(&guild_id.0)
+        .map(|loop_mode| *loop_mode)
+        .unwrap_or_default();
+
+    let track_data = get_track_data_lock(&ctx.data).await;
+    let tracks: Vec<SavedTrack> = queue
+        .current_queue()
+        .iter()
+        .filter_map(|track| {
+            let requester = track_data.get(&track.uuid())?.requester;
+            SavedTrack::from_metadata(track.metadata(), requester)
+        })
+        .collect();
+    drop(track_data);
+
+    if tracks.is_empty() {
+        queue_sessions.remove(&guild_id.0);
+    } else if let Some(mut session) = queue_sessions.get_mut(&guild_id.0) {
+        session.tracks = tracks;
+        session.position = position;
+        session.loop_mode = loop_mode;
+    }
+
+    if let Err(why) = save_queue_sessions(&queue_sessions) {
+        println!("Err saving the queue of {guild_id}: {why:?}");
+    }
+}
+
+/// Restores, offers to restore or discards the queues that were saved before the bot restarted
+pub async fn restore_sessions(ctx: &Context) {
+    if SESSIONS_RESTORED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    let restore_mode = env::var("RESTORE_QUEUES")
+        .ok()
+        .and_then(|mode| RestoreMode::from_str(&mode.to_lowercase()).ok())
+        .unwrap_or_default();
+
+    let queue_sessions = get_queue_sessions_lock(&ctx.data).await;
+    if restore_mode == RestoreMode::Off {
+        queue_sessions.clear();
+        if let Err(why) = save_queue_sessions(&queue_sessions) {
+            println!("Err discarding the saved queues: {why:?}");
+        }
+        return;
+    }
+
+    let sessions: Vec<(GuildId, QueueSession)> = queue_sessions
+        .iter()
+        .map(|session| (GuildId(*session.key()), session.value().clone()))
+        .collect();
+
+    for (guild_id, session) in sessions {
+        let text_channel = ChannelId(session.text_channel);
+
+        let sent = if restore_mode == RestoreMode::Auto {
+            let content = match restore_session(ctx, guild_id).await {
+                Ok(count) => format!("Resumed the queue of {count} songs from before the restart"),
+                Err(why) => format!("Couldn't resume the queue from before the restart: {why}"),
+            };
+            text_channel.say(&ctx.http, content).await.map(|_| ())
+        } else {
+            send_restore_offer(ctx, text_channel, &session).await
+        };
+
+        if let Err(why) = sent {
+            println!("Err sending the queue restore message to {guild_id}: {why:?}");
+        }
+    }
+}
+
+/// Joins the voice channel the queue was playing in and queues its songs again,
+/// starting from where the current song was.
+///
+/// Returns how many songs were queued
+pub async fn restore_session(ctx: &Context, guild_id: GuildId) -> Result<usize, RestoreError> {
+    let Some(session) = get_queue_sessions_lock(&ctx.data)
+        .await
+        .get(&guild_id.0)
+        .map(|session| session.clone())
+    else {
+        return Err(RestoreError::NoSession);
+    };
+
+    let manager = songbird::get(ctx).await.expect("songbird error").clone();
+    if let Some(call_lock) = manager.get(guild_id) {
+        if !call_lock.lock().await.queue().is_empty() {
+            return Err(RestoreError::AlreadyPlaying);
+        }
+    }
+
+    let (call_lock, joined) = manager
+        .join(guild_id, ChannelId(session.voice_channel))
+        .await;
+    if joined.is_err() {
+        return Err(RestoreError::CouldntJoin);
+    }
+
+    let mut call = call_lock.lock().await;
+    add_queue_events(
+        ctx,
+        &call_lock,
+        &mut call,
+        guild_id,
+        ChannelId(session.text_channel),
+    );
+
+    let loop_modes = get_loop_modes_lock(&ctx.data).await;
+    if session.loop_mode == LoopMode::Off {
+        loop_modes.remove(&guild_id.0);
+    } else {
+        loop_modes.insert(guild_id.0, session.loop_mode);
+    }
+
+    let mut queued = 0;
+    for track in session.tracks {
+        let requester = track.requester.map(UserId);
+        let Some(input) = track.into_lazy_input(&ctx.data).await else {
+            continue;
+        };
+
+        enqueue_input_unsaved(ctx, &mut call, guild_id, input, requester).await;
+        queued += 1;
+    }
+    save_session(ctx, guild_id, call.queue()).await;
+
+    let Some(current) = call.queue().current() else {
+        call.leave().await.ok();
+        let queue_sessions = get_queue_sessions_lock(&ctx.data).await;
+        queue_sessions.remove(&guild_id.0);
+        save_queue_sessions(&queue_sessions).ok();
+        return Err(RestoreError::NoPlayableTracks);
+    };
+    if session.loop_mode == LoopMode::Track {
+        current.enable_loop().ok();
+    }
+    if !session.position.is_zero() {
+        current.seek_time(session.position).ok();
+    }
+
+    Ok(queued)
+}
+
+async fn send_restore_offer(
+    ctx: &Context,
+    text_channel: ChannelId,
+    session: &QueueSession,
+) -> serenity::Result<()> {
+    let colour = Colour::from_rgb(149, 8, 2);
+    let current_song = session
+        .tracks
+        .first()
+        .and_then(|track| track.title.clone())
+        .unwrap_or_else(|| "Unknown".to_owned());
+
+    text_channel
+        .send_message(&ctx.http, |m| {
+            m.embed(|e| {
+                e.title("The bot restarted while songs were queued")
+                    .description(format!(
+                        "Restore the queue of {} songs in <#{}>?",
+                        session.tracks.len(),
+                        session.voice_channel
+                    ))
+                    .field("Was playing", current_song, false)
+                    .colour(colour)
+            })
+            .components(|c| {
+                c.create_action_row(|a| {
+                    a.create_button(|b| {
+                        b.label("Restore")
+                            .style(ButtonStyle::Success)
+                            .custom_id(ButtonIds::RestoreQueue)
+                    })
+                    .create_button(|b| {
+                        b.label("Discard")
+                            .style(ButtonStyle::Secondary)
+                            .custom_id(ButtonIds::DiscardQueue)
+                    })
+                })
+            })
+        })
+        .await?;
+
+    Ok(())
+}
+
+/// Restores the queue from before the restart when the button is pressed
+pub async fn restore_queue_button(ctx: &Context, button: &MessageComponentInteraction) {
+    if !can_use_playback_button(ctx, button).await {
+        respond_ephemeral(ctx, button, "Only DJs can restore the queue!").await;
+        return;
+    }
+    let guild_id = button.guild_id.expect("Couldn't get guild ID");
+
+    button
+        .create_interaction_response(&ctx.http, |r| {
+            r.kind(InteractionResponseType::DeferredUpdateMessage)
+        })
+        .await
+        .expect("Error creating interaction response");
+
+    let content = match restore_session(ctx, guild_id).await {
+        Ok(count) => format!("Restored the queue of {count} songs"),
+        Err(why) => format!("Couldn't restore the queue: {why}"),
+    };
+
+    button
+        .edit_original_interaction_response(&ctx.http, |r| {
+            r.content(content).set_embeds(Vec::new()).components(|c| c)
+        })
+        .await
+        .expect("Error editing interaction response");
+}
+
+/// Forgets the queue from before the restart when the button is pressed
+pub async fn discard_queue_button(ctx: &Context, button: &MessageComponentInteraction) {
+    if !can_use_playback_button(ctx, button).await {
+        respond_ephemeral(ctx, button, "Only DJs can discard the queue!").await;
+        return;
+    }
+    let guild_id = button.guild_id.expect("Couldn't get guild ID");
+
+    let manager = songbird::get(ctx).await.expect("songbird error").clone();
+    let is_playing = match manager.get(guild_id) {
+        Some(call_lock) => !call_lock.lock().await.queue().is_empty(),
+        None => false,
+    };
+
+    // A new queue replaces the saved one, so only the old one is removed
+    let content = if is_playing {
+        "A new queue was already started".to_owned()
+    } else {
+        let queue_sessions = get_queue_sessions_lock(&ctx.data).await;
+        queue_sessions.remove(&guild_id.0);
+        match save_queue_sessions(&queue_sessions) {
+            Ok(()) => "The queue from before the restart was discarded".to_owned(),
+            Err(_) => "Something went wrong while discarding the queue".to_owned(),
+        }
+    };
+
+    button
+        .create_interaction_response(&ctx.http, |r| {
+            r.kind(InteractionResponseType::UpdateMessage)
+                .interaction_response_data(|d| {
+                    d.content(content).set_embeds(Vec::new()).components(|c| c)
+                })
+        })
+        .await
+        .expect("Error creating interaction response");
+}
@@ -0,0 +1,67 @@
+use std::ops::ControlFlow;
+
+use rand::seq::SliceRandom;
+use serenity::{
+    client::Context,
+    model::prelude::interaction::application_command::ApplicationCommandInteraction,
+};
+use songbird::tracks::TrackQueue;
+use thiserror::Error;
+
+use super::{
+    helper_funcs::{get_call_lock, respond_if_not_same_vc},
+    permissions::respond_if_not_dj,
+    sessions::save_session,
+};
+use crate::client::helper_funcs::respond_with_content;
+
+pub trait Shuffleable {
+    /// Shuffles every track after the one that's playing
+    fn shuffle(&self) -> Result<(), ShuffleError>;
+}
+
+#[derive(Debug, Error)]
+pub enum ShuffleError {
+    #[error("There aren't enough songs in the queue to shuffle")]
+    NotEnoughSongs,
+}
+
+impl Shuffleable for TrackQueue {
+    fn shuffle(&self) -> Result<(), ShuffleError> {
+        self.modify_queue(|q| {
+            if q.len() < 3 {
+                return Err(ShuffleError::NotEnoughSongs);
+            }
+
+            q.make_contiguous()[1..].shuffle(&mut rand::thread_rng());
+
+            Ok(())
+        })
+    }
+}
+
+/// Shuffle the queue, the current track keeps playing
+pub async fn shuffle(ctx: &Context, command: &ApplicationCommandInteraction) {
+    let guild_id = command.guild_id.expect("Couldn't get guild ID");
+
+    if let ControlFlow::Break(()) = respond_if_not_dj(ctx, command).await {
+        return;
+    }
+
+    if let ControlFlow::Break(()) = respond_if_not_same_vc(guild_id, ctx, command).await {
+        return;
+    }
+
+    let Some(call_lock) = get_call_lock(ctx, guild_id, command).await else {
+        return;
+    };
+    let call = call_lock.lock().await;
+
+    let response = match call.queue().shuffle() {
+        Ok(()) => "Shuffled the queue.".to_owned(),
+        Err(why) => format!("{why}!"),
+    };
+    save_session(ctx, guild_id, call.queue()).await;
+
+    respond_with_content(ctx, command, response).await;
+}
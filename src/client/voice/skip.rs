@@ -5,12 +5,21 @@ use serenity::{
     utils::Colour, model::prelude::interaction::application_command::{ApplicationCommandInteraction, CommandDataOptionValue},
 };
 
-use super::helper_funcs::{get_call_lock, is_bot_in_another_channel};
+use super::{
+    helper_funcs::{get_call_lock, respond_if_not_same_vc},
+    permissions::respond_if_not_dj,
+    queue_edit::discard_tracks,
+    sessions::save_session,
+};
 
 /// Skip the track
 pub async fn skip(ctx: &Context, command: &ApplicationCommandInteraction) {
     let guild_id = command.guild_id.expect("Couldn't get guild ID");
 
+    if let ControlFlow::Break(()) = respond_if_not_dj(ctx, command).await {
+        return;
+    }
+
     if let ControlFlow::Break(_) = respond_if_not_same_vc(guild_id, ctx, command).await {
         return;
     }
@@ -33,11 +42,18 @@ pub async fn skip(ctx: &Context, command: &ApplicationCommandInteraction) {
 
     let track_number = get_track_number(command);
 
-    if track_number.is_some() {
-        let success = if track_number.unwrap() == 1 {
+    if let Some(track_number) = track_number {
+        let success = if track_number == 1 {
             call.queue().skip().is_ok()
         } else {
-            call.queue().dequeue(track_number.unwrap() - 1).is_some()
+            match call.queue().dequeue(track_number - 1) {
+                Some(track) => {
+                    discard_tracks(ctx, vec![track]).await;
+                    save_session(ctx, guild_id, call.queue()).await;
+                    true
+                }
+                None => false,
+            }
         };
 
         if !success {
@@ -67,27 +83,6 @@ pub async fn skip(ctx: &Context, command: &ApplicationCommandInteraction) {
         .expect("Error creating interaction response");
 }
 
-async fn respond_if_not_same_vc(
-    guild_id: serenity::model::id::GuildId,
-    ctx: &Context,
-    command: &ApplicationCommandInteraction,
-) -> ControlFlow<()> {
-    if let Some(guild) = guild_id.to_guild_cached(&ctx.cache) {
-        if is_bot_in_another_channel(ctx, &guild, command.user.id) {
-            command
-                .create_interaction_response(&ctx.http, |r| {
-                    r.interaction_response_data(|d| {
-                        d.content("Must be in the same voice channel to use that command!")
-                    })
-                })
-                .await
-                .expect("Error creating interaction response");
-            return ControlFlow::Break(());
-        }
-    }
-    ControlFlow::Continue(())
-}
-
 fn get_track_number(command: &ApplicationCommandInteraction) -> Option<usize> {
     let track_number = command
         .data
@@ -0,0 +1,527 @@
+use std::{
+    env,
+    path::{Path, PathBuf},
+    process::Stdio,
+    sync::Arc,
+    time::Duration,
+};
+
+use serde_json::Value;
+use serenity::{
+    async_trait,
+    prelude::{RwLock, TypeMap},
+};
+use songbird::input::{
+    error::Error as InputError, ffmpeg_optioned, restartable::Restart, Codec, Container, Input,
+    Metadata, Restartable,
+};
+use thiserror::Error;
+use tokio::process::Command;
+
+use super::{
+    global_data::get_source_resolvers_lock,
+    local::{find_local_file, get_local_music_dir},
+    playlist::{list_ytdl_entries, PlaylistEntry, PlaylistError},
+};
+
+/// The only hosts audio links are played from, anything else goes through youtube-dl
+const AUDIO_LINK_HOSTS: &[&str] = &["cdn.discordapp.com", "media.discordapp.net"];
+
+/// Audio formats ffmpeg plays directly, without youtube-dl
+pub const AUDIO_EXTENSIONS: &[&str] = &["mp3", "ogg", "opus", "wav", "flac", "m4a", "aac", "webm"];
+
+#[derive(Debug, Error)]
+pub enum ResolveError {
+    #[error("nothing can play {0}")]
+    NoResolver(String),
+    #[error("couldn't start the song: {0}")]
+    Input(#[from] InputError),
+}
+
+/// Turns what a user asked to play into audio songbird can play
+#[async_trait]
+pub trait SourceResolver: Send + Sync {
+    /// Checks if the query is something this resolver plays
+    fn can_resolve(&self, query: &str) -> bool;
+
+    /// Lazy inputs don't start downloading until the track is about to be played
+    async fn resolve(&self, query: &str, lazy: bool) -> Result<Input, ResolveError>;
+
+    /// Lists at most `max` tracks of a playlist or search without downloading them,
+    /// and whether there were more than that. `None` if this resolver can't read playlists
+    async fn list_entries(
+        &self,
+        _query: &str,
+        _max: usize,
+    ) -> Option<Result<(Vec<PlaylistEntry>, bool), PlaylistError>> {
+        None
+    }
+}
+
+/// Audio files in the music folder, given by their path in it with or without `file://` in front.
+///
+/// It isn't one of the default resolvers so users can't play files from anywhere on the bot's machine
+pub struct LocalFileResolver {
+    pub music_dir: PathBuf,
+}
+
+#[async_trait]
+impl SourceResolver for LocalFileResolver {
+    fn can_resolve(&self, query: &str) -> bool {
+        find_local_file(&self.music_dir, query.trim_start_matches("file://")).is_some()
+    }
+
+    async fn resolve(&self, query: &str, lazy: bool) -> Result<Input, ResolveError> {
+        let name = query.trim_start_matches("file://");
+        let Some(path) = find_local_file(&self.music_dir, name) else {
+            return Err(ResolveError::NoResolver(query.to_owned()));
+        };
+
+        let mut input: Input = Restartable::ffmpeg(path.clone(), lazy).await?.into();
+        set_title_from_path(&mut input, name);
+        // Saved relative to the folder so the track still plays if the folder moves
+        if let Ok(relative_path) = path.strip_prefix(&self.music_dir) {
+            input.metadata.source_url = Some(format!("file://{}", relative_path.display()));
+        }
+
+        Ok(input)
+    }
+}
+
+/// Files uploaded to discord, which are played without youtube-dl
+pub struct HttpAudioResolver;
+
+#[async_trait]
+impl SourceResolver for HttpAudioResolver {
+    fn can_resolve(&self, query: &str) -> bool {
+        let Some(rest) = query.strip_prefix("https://") else {
+            return false;
+        };
+        let host = rest.split(['/', '?', '#']).next().unwrap_or_default();
+
+        AUDIO_LINK_HOSTS
+            .iter()
+            .any(|allowed_host| host.eq_ignore_ascii_case(allowed_host))
+    }
+
+    async fn resolve(&self, query: &str, lazy: bool) -> Result<Input, ResolveError> {
+        let mut input: Input = Restartable::ffmpeg(query.to_owned(), lazy).await?.into();
+        set_title_from_path(&mut input, query.split(['?', '#']).next().unwrap_or(query));
+
+        Ok(input)
+    }
+}
+
+/// Anything youtube-dl or a compatible program like yt-dlp supports, queries that aren't links are searched on youtube
+pub struct YtdlResolver {
+    /// The youtube-dl compatible program, set with `YTDL_PATH`
+    pub path: String,
+}
+
+impl Default for YtdlResolver {
+    fn default() -> Self {
+        Self {
+            path: get_ytdl_path(),
+        }
+    }
+}
+
+#[async_trait]
+impl SourceResolver for YtdlResolver {
+    /// Searches and web links, other schemes like `file://` could make youtube-dl read the bot's files
+    fn can_resolve(&self, query: &str) -> bool {
+        !query.contains("://") || query.starts_with("https://") || query.starts_with("http://")
+    }
+
+    async fn resolve(&self, query: &str, lazy: bool) -> Result<Input, ResolveError> {
+        let uri = if query.contains("://") {
+            query.to_owned()
+        } else {
+            format!("ytsearch1:{query}")
+        };
+
+        let restarter = YtdlRestarter {
+            path: self.path.clone(),
+            uri,
+        };
+
+        Ok(Restartable::new(restarter, lazy).await?.into())
+    }
+
+    async fn list_entries(
+        &self,
+        query: &str,
+        max: usize,
+    ) -> Option<Result<(Vec<PlaylistEntry>, bool), PlaylistError>> {
+        Some(list_ytdl_entries(&self.path, query, max).await)
+    }
+}
+
+/// Starts youtube-dl again to seek, or when a lazy track starts playing
+struct YtdlRestarter {
+    path: String,
+    uri: String,
+}
+
+#[async_trait]
+impl Restart for YtdlRestarter {
+    async fn call_restart(
+        &mut self,
+        time: Option<Duration>,
+    ) -> songbird::input::error::Result<Input> {
+        let output = Command::new(&self.path)
+            .args([
+                "-j",
+                "-f",
+                "webm[abr>0]/bestaudio/best",
+                "--no-playlist",
+                "--ignore-config",
+                "--no-warnings",
+            ])
+            .arg(&self.uri)
+            .stdin(Stdio::null())
+            .output()
+            .await?;
+
+        if !output.status.success() {
+            return Err(InputError::YouTubeDlRun(output));
+        }
+
+        let value: Value =
+            serde_json::from_slice(&output.stdout).map_err(|error| InputError::Json {
+                error,
+                parsed_text: String::from_utf8_lossy(&output.stdout).into_owned(),
+            })?;
+        let Some(stream_url) = value["url"].as_str().map(ToOwned::to_owned) else {
+            return Err(InputError::YouTubeDlUrl(value));
+        };
+
+        let seek_args = time.map(|time| format!("{:.3}", time.as_secs_f64()));
+        let pre_input_args: Vec<&str> = match &seek_args {
+            Some(seconds) => vec!["-ss", seconds],
+            None => Vec::new(),
+        };
+
+        let mut input = ffmpeg_optioned(
+            stream_url,
+            &pre_input_args,
+            &[
+                "-f",
+                "s16le",
+                "-ac",
+                "2",
+                "-ar",
+                "48000",
+                "-acodec",
+                "pcm_f32le",
+                "-",
+            ],
+        )
+        .await?;
+        input.metadata = Box::new(Metadata::from_ytdl_output(value));
+
+        Ok(input)
+    }
+
+    async fn lazy_init(
+        &mut self,
+    ) -> songbird::input::error::Result<(Option<Metadata>, Codec, Container)> {
+        // youtube-dl isn't started until the track plays, so the rest of the metadata has to be filled in by the caller.
+        // The channels have to be set here, songbird reads lazy inputs as mono without them
+        let metadata = Metadata {
+            channels: Some(2),
+            ..Default::default()
+        };
+
+        Ok((Some(metadata), Codec::FloatPcm, Container::Raw))
+    }
+}
+
+/// The youtube-dl compatible program used to play and search songs, set with `YTDL_PATH`
+pub fn get_ytdl_path() -> String {
+    env::var("YTDL_PATH").unwrap_or_else(|_| "youtube-dl".to_owned())
+}
+
+/// Plays the query with the first resolver that can.
+///
+/// The query is kept as the source url if the resolver didn't find a better one,
+/// so the track can be played again later
+pub async fn resolve_source(
+    data: &Arc<RwLock<TypeMap>>,
+    query: &str,
+    lazy: bool,
+) -> Result<Input, ResolveError> {
+    let resolvers = get_source_resolvers_lock(data).await;
+    let Some(resolver) = resolvers
+        .iter()
+        .find(|resolver| resolver.can_resolve(query))
+    else {
+        return Err(ResolveError::NoResolver(query.to_owned()));
+    };
+
+    resolve_with(resolver.as_ref(), query, lazy).await
+}
+
+/// Plays a track that was queued before, local files are only played from the music folder
+pub async fn resolve_saved_source(
+    data: &Arc<RwLock<TypeMap>>,
+    source: &str,
+    lazy: bool,
+) -> Result<Input, ResolveError> {
+    if !source.starts_with("file://") {
+        return resolve_source(data, source, lazy).await;
+    }
+
+    let Some(music_dir) = get_local_music_dir() else {
+        return Err(ResolveError::NoResolver(source.to_owned()));
+    };
+
+    resolve_with(&LocalFileResolver { music_dir }, source, lazy).await
+}
+
+/// Plays the query with the resolver, keeping the query as the source url if it didn't find one
+pub async fn resolve_with(
+    resolver: &dyn SourceResolver,
+    query: &str,
+    lazy: bool,
+) -> Result<Input, ResolveError> {
+    let mut input = resolver.resolve(query, lazy).await?;
+    if input.metadata.source_url.is_none() {
+        input.metadata.source_url = Some(query.to_owned());
+    }
+
+    Ok(input)
+}
+
+pub fn has_audio_extension(path: &Path) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| AUDIO_EXTENSIONS.contains(&extension.to_lowercase().as_str()))
+}
+
+/// Files usually don't have a title in their metadata, so their name is used instead
+fn set_title_from_path(input: &mut Input, path: &str) {
+    if input.metadata.title.is_none() {
+        input.metadata.title = Path::new(path)
+            .file_stem()
+            .map(|name| name.to_string_lossy().into_owned());
+    }
+}
+
+/// Youtube-dl comes last because it plays anything the others don't.
+///
+/// [`LocalFileResolver`] isn't included, local files are only played with `/play-local`
+pub fn default_source_resolvers() -> Vec<Box<dyn SourceResolver>> {
+    vec![
+        Box::new(HttpAudioResolver),
+        Box::new(YtdlResolver::default()),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        resolve_source, HttpAudioResolver, LocalFileResolver, ResolveError, SourceResolver,
+        YtdlResolver,
+    };
+    use crate::client::voice::{
+        global_data::SourceResolvers,
+        playlist::{get_playlist_entries, PlaylistEntry, PlaylistError},
+    };
+    use serenity::{
+        async_trait,
+        prelude::{RwLock, TypeMap},
+    };
+    use songbird::input::{Codec, Container, Input, Metadata, Reader};
+    use std::{fs, sync::Arc};
+
+    /// Plays queries starting with the prefix, the title says which mock played it
+    struct MockResolver {
+        prefix: &'static str,
+        title: &'static str,
+        source_url: Option<&'static str>,
+    }
+
+    #[async_trait]
+    impl SourceResolver for MockResolver {
+        fn can_resolve(&self, query: &str) -> bool {
+            query.starts_with(self.prefix)
+        }
+
+        async fn resolve(&self, _query: &str, _lazy: bool) -> Result<Input, ResolveError> {
+            let metadata = Metadata {
+                title: Some(self.title.to_owned()),
+                source_url: self.source_url.map(ToOwned::to_owned),
+                ..Default::default()
+            };
+
+            Ok(Input::new(
+                true,
+                Reader::from_memory(Vec::new()),
+                Codec::FloatPcm,
+                Container::Raw,
+                Some(metadata),
+            ))
+        }
+
+        async fn list_entries(
+            &self,
+            query: &str,
+            _max: usize,
+        ) -> Option<Result<(Vec<PlaylistEntry>, bool), PlaylistError>> {
+            let entry = PlaylistEntry {
+                url: query.to_owned(),
+                title: Some(self.title.to_owned()),
+                channel: None,
+                duration: None,
+            };
+
+            Some(Ok((vec![entry], false)))
+        }
+    }
+
+    fn data_with_resolvers(resolvers: Vec<Box<dyn SourceResolver>>) -> Arc<RwLock<TypeMap>> {
+        let mut data = TypeMap::new();
+        data.insert::<SourceResolvers>(Arc::new(resolvers));
+        Arc::new(RwLock::new(data))
+    }
+
+    #[tokio::test]
+    async fn first_matching_resolver_is_used() {
+        let data = data_with_resolvers(vec![
+            Box::new(MockResolver {
+                prefix: "first",
+                title: "first",
+                source_url: None,
+            }),
+            Box::new(MockResolver {
+                prefix: "",
+                title: "fallback",
+                source_url: None,
+            }),
+            Box::new(MockResolver {
+                prefix: "first",
+                title: "never",
+                source_url: None,
+            }),
+        ]);
+
+        let input = resolve_source(&data, "first song", false).await.unwrap();
+        assert_eq!(input.metadata.title.as_deref(), Some("first"));
+
+        let input = resolve_source(&data, "other song", false).await.unwrap();
+        assert_eq!(input.metadata.title.as_deref(), Some("fallback"));
+    }
+
+    #[tokio::test]
+    async fn queries_nothing_can_play_are_rejected() {
+        let data = data_with_resolvers(vec![Box::new(MockResolver {
+            prefix: "https://",
+            title: "link",
+            source_url: None,
+        })]);
+
+        assert!(matches!(
+            resolve_source(&data, "song", false).await,
+            Err(ResolveError::NoResolver(query)) if query == "song"
+        ));
+    }
+
+    #[tokio::test]
+    async fn query_is_kept_as_the_source_url() {
+        let data = data_with_resolvers(vec![
+            Box::new(MockResolver {
+                prefix: "found",
+                title: "found",
+                source_url: Some("https://example.com/found"),
+            }),
+            Box::new(MockResolver {
+                prefix: "",
+                title: "fallback",
+                source_url: None,
+            }),
+        ]);
+
+        let input = resolve_source(&data, "found song", true).await.unwrap();
+        assert_eq!(
+            input.metadata.source_url.as_deref(),
+            Some("https://example.com/found")
+        );
+
+        let input = resolve_source(&data, "song", true).await.unwrap();
+        assert_eq!(input.metadata.source_url.as_deref(), Some("song"));
+    }
+
+    #[test]
+    fn only_discord_attachments_are_played_as_audio_links() {
+        let resolver = HttpAudioResolver;
+
+        assert!(resolver.can_resolve("https://cdn.discordapp.com/attachments/1/2/song.mp3"));
+        assert!(resolver.can_resolve("https://media.discordapp.net/attachments/1/2/song.ogg?x=1"));
+        assert!(!resolver.can_resolve("http://cdn.discordapp.com/attachments/1/2/song.mp3"));
+        assert!(!resolver.can_resolve("https://example.com/song.mp3"));
+        assert!(!resolver.can_resolve("https://127.0.0.1/song.mp3"));
+        assert!(!resolver.can_resolve("https://cdn.discordapp.com.example.com/song.mp3"));
+        assert!(!resolver.can_resolve("https://cdn.discordapp.com@127.0.0.1/song.mp3"));
+        assert!(!resolver.can_resolve("file:///song.mp3"));
+        assert!(!resolver.can_resolve("song.mp3"));
+    }
+
+    #[test]
+    fn youtube_dl_only_gets_searches_and_web_links() {
+        let resolver = YtdlResolver {
+            path: "youtube-dl".to_owned(),
+        };
+
+        assert!(resolver.can_resolve("never gonna give you up"));
+        assert!(resolver.can_resolve("https://www.youtube.com/watch?v=dQw4w9WgXcQ"));
+        assert!(!resolver.can_resolve("file:///etc/passwd"));
+        assert!(!resolver.can_resolve("ftp://example.com/song.mp3"));
+    }
+
+    #[tokio::test]
+    async fn local_resolver_strips_the_file_prefix() {
+        let root = std::env::temp_dir().join(format!("markov_bot_sources_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(&root).unwrap();
+        fs::write(root.join("song.mp3"), "").unwrap();
+
+        let resolver = LocalFileResolver {
+            music_dir: fs::canonicalize(&root).unwrap(),
+        };
+
+        assert!(resolver.can_resolve("file://song.mp3"));
+        assert!(!resolver.can_resolve("file://missing.mp3"));
+        assert!(matches!(
+            resolver.resolve("file://missing.mp3", true).await,
+            Err(ResolveError::NoResolver(query)) if query == "file://missing.mp3"
+        ));
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[tokio::test]
+    async fn playlists_are_listed_by_the_matching_resolver() {
+        let data = data_with_resolvers(vec![
+            Box::new(MockResolver {
+                prefix: "other",
+                title: "other",
+                source_url: None,
+            }),
+            Box::new(MockResolver {
+                prefix: "list",
+                title: "list",
+                source_url: None,
+            }),
+        ]);
+
+        let (entries, cut_off) = get_playlist_entries(&data, "list:songs", 10).await.unwrap();
+        assert_eq!(entries[0].title.as_deref(), Some("list"));
+        assert!(!cut_off);
+
+        assert!(matches!(
+            get_playlist_entries(&data, "nothing", 10).await,
+            Err(PlaylistError::NoResolver)
+        ));
+    }
+}
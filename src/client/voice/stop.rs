@@ -1,14 +1,22 @@
+use std::ops::ControlFlow;
+
 use serenity::{
     client::Context,
     utils::Colour, model::prelude::interaction::application_command::ApplicationCommandInteraction,
 };
 
-use super::helper_funcs::is_bot_in_another_channel;
+use super::{
+    helper_funcs::is_bot_in_another_channel, permissions::respond_if_not_dj, sessions::save_session,
+};
 
 ///stop playing
 pub async fn stop(ctx: &Context, command: &ApplicationCommandInteraction) {
     let guild_id = command.guild_id.expect("Couldn't get guild ID");
 
+    if let ControlFlow::Break(()) = respond_if_not_dj(ctx, command).await {
+        return;
+    }
+
     if let Some(guild) = guild_id.to_guild_cached(&ctx.cache) {
         if is_bot_in_another_channel(ctx, &guild, command.user.id) {
             command
@@ -32,6 +40,7 @@ pub async fn stop(ctx: &Context, command: &ApplicationCommandInteraction) {
         let handler = handler_lock.lock().await;
         let queue = handler.queue();
         queue.stop();
+        save_session(ctx, guild_id, queue).await;
     } else {
         command
             .create_interaction_response(&ctx.http, |r| {
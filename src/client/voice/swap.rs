@@ -4,7 +4,10 @@ use serenity::{
 use songbird::tracks::TrackQueue;
 use thiserror::Error;
 
-use super::helper_funcs::{get_call_lock, is_bot_in_another_channel};
+use super::{
+    helper_funcs::{get_call_lock, is_bot_in_another_channel},
+    sessions::save_session,
+};
 
 pub trait Swapable {
     fn swap(&self, first_track_idx: usize, second_track_idx: usize) -> Result<(), SwapError>;
@@ -121,6 +124,7 @@ pub async fn swap_songs(ctx: &Context, command: &ApplicationCommandInteraction)
 
     match queue.swap(first_track_idx, second_track_idx) {
         Ok(_) => {
+            save_session(ctx, guild_id, queue).await;
             command
                 .create_interaction_response(&ctx.http, |r| {
                     r.interaction_response_data(|d| {
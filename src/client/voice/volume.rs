@@ -0,0 +1,81 @@
+use std::ops::ControlFlow;
+
+use super::{
+    global_data::{get_voice_settings_lock, save_voice_settings, DEFAULT_VOLUME, MAX_VOLUME},
+    helper_funcs::{get_call_lock, respond_if_not_same_vc},
+    permissions::respond_if_not_dj,
+};
+use crate::client::helper_funcs::{get_option, respond_with_content};
+use serenity::{
+    client::Context,
+    model::{
+        id::GuildId,
+        prelude::interaction::application_command::{
+            ApplicationCommandInteraction, CommandDataOptionValue,
+        },
+    },
+};
+
+/// The volume tracks of the guild start at, as the multiplier songbird uses
+pub async fn get_guild_volume(ctx: &Context, guild_id: GuildId) -> f32 {
+    let volume = get_voice_settings_lock(&ctx.data)
+        .await
+        .get(&guild_id.0)
+        .map_or(DEFAULT_VOLUME, |settings| settings.volume);
+
+    volume_multiplier(volume)
+}
+
+/// Changes the volume of the current track and every track queued after it
+pub async fn volume(ctx: &Context, command: &ApplicationCommandInteraction) {
+    let guild_id = command.guild_id.expect("Couldn't get guild ID");
+
+    let Some(CommandDataOptionValue::Integer(percent)) = get_option(command, "percent") else {
+        respond_with_content(ctx, command, "Something went wrong").await;
+        return;
+    };
+    let Some(percent) = u8::try_from(*percent)
+        .ok()
+        .filter(|percent| *percent <= MAX_VOLUME)
+    else {
+        respond_with_content(
+            ctx,
+            command,
+            format!("The volume has to be between 0 and {MAX_VOLUME}"),
+        )
+        .await;
+        return;
+    };
+
+    if let ControlFlow::Break(()) = respond_if_not_dj(ctx, command).await {
+        return;
+    }
+
+    if let ControlFlow::Break(()) = respond_if_not_same_vc(guild_id, ctx, command).await {
+        return;
+    }
+
+    let Some(call_lock) = get_call_lock(ctx, guild_id, command).await else {
+        return;
+    };
+    let call = call_lock.lock().await;
+
+    for track in call.queue().current_queue() {
+        // Tracks that already ended can't be changed and don't need to be
+        let _ = track.set_volume(volume_multiplier(percent));
+    }
+
+    let voice_settings = get_voice_settings_lock(&ctx.data).await;
+    voice_settings.entry(guild_id.0).or_default().volume = percent;
+
+    let response = match save_voice_settings(&voice_settings) {
+        Ok(()) => format!("The volume is now {percent}%"),
+        Err(_) => format!("The volume is now {percent}%, but it couldn't be saved for later songs"),
+    };
+
+    respond_with_content(ctx, command, response).await;
+}
+
+fn volume_multiplier(percent: u8) -> f32 {
+    f32::from(percent) / 100.0
+}